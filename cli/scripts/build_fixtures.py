@@ -0,0 +1,505 @@
+#!/usr/bin/env python3
+"""Builds the fixtures of tests/decoding.rs that aren't recorded from mainnet.
+
+Each fixture is a getTransaction response, in the json encoding, of a transaction made up
+around the SPL Token and Associated Token Account programs, so that every instruction is
+covered in its checked and unchecked versions and signed by a single authority or a
+multisig. The accounts other than the programs, sysvars and mints are derived from their
+name. Their golden files are then written by running the tests with UPDATE_GOLDEN=1, and
+both are reviewed before committing them.
+
+    python3 scripts/build_fixtures.py  # from the cli directory
+"""
+
+import hashlib
+import json
+import struct
+from pathlib import Path
+
+FIXTURES = Path(__file__).resolve().parent.parent / "tests/fixtures"
+
+ALPHABET = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz"
+
+
+def b58encode(data):
+    n = int.from_bytes(data, "big")
+    out = ""
+    while n:
+        n, r = divmod(n, 58)
+        out = ALPHABET[r] + out
+    pad = len(data) - len(data.lstrip(b"\0"))
+    return "1" * pad + out
+
+
+def b58decode(s):
+    n = 0
+    for c in s:
+        n = n * 58 + ALPHABET.index(c)
+    raw = n.to_bytes((n.bit_length() + 7) // 8, "big")
+    pad = len(s) - len(s.lstrip("1"))
+    return b"\0" * pad + raw
+
+
+TOKEN = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+ATA = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
+SYSTEM = "11111111111111111111111111111111"
+RENT = "SysvarRent111111111111111111111111111111111"
+USDC = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"
+WSOL = "So11111111111111111111111111111111111111112"
+PROGRAMS = {TOKEN, ATA, SYSTEM}
+SYSVARS = {RENT}
+
+
+def key(name):
+    """A deterministic address standing for the account `name` of a fixture."""
+    return b58encode(hashlib.sha256(f"spl-token-fixture:{name}".encode()).digest())
+
+
+def u64(v):
+    return struct.pack("<Q", v)
+
+
+def opt_key(k):
+    return b"\x01" + b58decode(k) if k else b"\x00"
+
+
+# SPL Token instructions
+def transfer(amount):
+    return bytes([3]) + u64(amount)
+
+
+def transfer_checked(amount, decimals):
+    return bytes([12]) + u64(amount) + bytes([decimals])
+
+
+def approve(amount):
+    return bytes([4]) + u64(amount)
+
+
+def approve_checked(amount, decimals):
+    return bytes([13]) + u64(amount) + bytes([decimals])
+
+
+def mint_to(amount):
+    return bytes([7]) + u64(amount)
+
+
+def mint_to_checked(amount, decimals):
+    return bytes([14]) + u64(amount) + bytes([decimals])
+
+
+def burn(amount):
+    return bytes([8]) + u64(amount)
+
+
+def burn_checked(amount, decimals):
+    return bytes([15]) + u64(amount) + bytes([decimals])
+
+
+def set_authority(authority_type, new_authority):
+    return bytes([6, authority_type]) + opt_key(new_authority)
+
+
+def initialize_mint(decimals, mint_authority, freeze_authority, v2=False):
+    data = bytes([20 if v2 else 0, decimals]) + b58decode(mint_authority)
+    return data + opt_key(freeze_authority)
+
+
+def initialize_account2(owner, v3=False):
+    return bytes([18 if v3 else 16]) + b58decode(owner)
+
+
+def initialize_multisig(m, v2=False):
+    return bytes([19 if v2 else 2, m])
+
+
+REVOKE = bytes([5])
+CLOSE_ACCOUNT = bytes([9])
+FREEZE_ACCOUNT = bytes([10])
+THAW_ACCOUNT = bytes([11])
+INITIALIZE_ACCOUNT = bytes([1])
+SYNC_NATIVE = bytes([17])
+GET_ACCOUNT_DATA_SIZE = bytes([21])
+INITIALIZE_IMMUTABLE_OWNER = bytes([22])
+
+MINT_TOKENS, FREEZE_ACCOUNT_AUTHORITY, ACCOUNT_OWNER, CLOSE_ACCOUNT_AUTHORITY = range(4)
+
+LOG_NAMES = {
+    0: "InitializeMint", 1: "InitializeAccount", 2: "InitializeMultisig", 3: "Transfer",
+    4: "Approve", 5: "Revoke", 6: "SetAuthority", 7: "MintTo", 8: "Burn", 9: "CloseAccount",
+    10: "FreezeAccount", 11: "ThawAccount", 12: "TransferChecked", 13: "ApproveChecked",
+    14: "MintToChecked", 15: "BurnChecked", 16: "InitializeAccount2", 17: "SyncNative",
+    18: "InitializeAccount3", 19: "InitializeMultisig2", 20: "InitializeMint2",
+    21: "GetAccountDataSize", 22: "InitializeImmutableOwner",
+}
+
+
+def system_create_account(lamports, space, owner):
+    return struct.pack("<I", 0) + u64(lamports) + u64(space) + b58decode(owner)
+
+
+def system_transfer(lamports):
+    return struct.pack("<I", 2) + u64(lamports)
+
+
+TOKEN_ACCOUNT_RENT = 2039280
+MINT_RENT = 1461600
+MULTISIG_RENT = 2463840
+
+
+class Tx:
+    def __init__(self, seed, signers):
+        self.seed = seed
+        self.signers = list(signers)
+        self.instructions = []
+        self.lamports = {}
+        self.token_balances = []
+
+    def ix(self, program, accounts, data, inner=()):
+        self.instructions.append((program, list(accounts), data, list(inner)))
+        return self
+
+    def balance(self, account, pre, post):
+        self.lamports[account] = (pre, post)
+        return self
+
+    def token(self, account, mint, owner, pre, post, decimals=6):
+        self.token_balances.append((account, mint, owner, pre, post, decimals))
+        return self
+
+    def keys(self):
+        order = list(self.signers)
+        seen = set(order)
+        for program, accounts, _, inner in self.instructions:
+            for account in accounts + [program] + [
+                a for (p, acc, _) in inner for a in acc + [p]
+            ]:
+                if account not in seen:
+                    seen.add(account)
+                    order.append(account)
+        unsigned = order[len(self.signers):]
+        readonly = [a for a in unsigned if a in PROGRAMS or a in SYSVARS]
+        writable = [a for a in unsigned if a not in readonly]
+        return self.signers + writable + readonly, len(readonly)
+
+    def response(self, slot, block_time):
+        keys, readonly = self.keys()
+        index = {k: i for i, k in enumerate(keys)}
+
+        def compile(program, accounts, data, stack_height=None):
+            compiled = {
+                "accounts": [index[a] for a in accounts],
+                "data": b58encode(data),
+                "programIdIndex": index[program],
+            }
+            if stack_height is not None:
+                compiled["stackHeight"] = stack_height
+            return compiled
+
+        logs = []
+        inner_instructions = []
+        compute_units = 0
+
+        def log(program, data, depth):
+            logs.append(f"Program {program} invoke [{depth}]")
+            if program == TOKEN:
+                logs.append(f"Program log: Instruction: {LOG_NAMES[data[0]]}")
+            elif program == ATA:
+                names = {b"\x01": "CreateIdempotent", b"\x02": "RecoverNested"}
+                logs.append(f"Program log: {names.get(data[:1], 'Create')}")
+
+        def done(program, units):
+            nonlocal compute_units
+            compute_units += units
+            if program == SYSTEM:
+                logs.append(f"Program {program} success")
+            else:
+                logs.append(f"Program {program} consumed {units} of 200000 compute units")
+                logs.append(f"Program {program} success")
+
+        for i, (program, accounts, data, inner) in enumerate(self.instructions):
+            log(program, data, 1)
+            if inner:
+                inner_instructions.append({
+                    "index": i,
+                    "instructions": [compile(p, a, d, 2) for (p, a, d) in inner],
+                })
+                for p, a, d in inner:
+                    log(p, d, 2)
+                    done(p, 0 if p == SYSTEM else 2900)
+            done(program, 20000 if program == ATA else 4500)
+
+        fee = 5000 * len(self.signers)
+        payer = self.signers[0]
+        pre_balances, post_balances = [], []
+        for k in keys:
+            if k in self.lamports:
+                pre, post = self.lamports[k]
+            elif k in PROGRAMS:
+                pre = post = 1141440 if k != SYSTEM else 1
+            elif k in SYSVARS:
+                pre = post = 1009200
+            elif k == payer:
+                pre = post = 1_500_000_000
+            elif k in self.signers:
+                pre = post = 250_000_000
+            else:
+                pre = post = TOKEN_ACCOUNT_RENT
+            if k == payer:
+                post -= fee
+            pre_balances.append(pre)
+            post_balances.append(post)
+
+        def token_balance(account, mint, owner, amount, decimals):
+            ui = amount / 10 ** decimals
+            return {
+                "accountIndex": index[account],
+                "mint": mint,
+                "owner": owner,
+                "programId": TOKEN,
+                "uiTokenAmount": {
+                    "amount": str(amount),
+                    "decimals": decimals,
+                    "uiAmount": ui if amount else None,
+                    "uiAmountString": f"{ui:.{decimals}f}".rstrip("0").rstrip("."),
+                },
+            }
+
+        pre_token_balances = [
+            token_balance(a, m, o, pre, d)
+            for (a, m, o, pre, post, d) in self.token_balances if pre is not None
+        ]
+        post_token_balances = [
+            token_balance(a, m, o, post, d)
+            for (a, m, o, pre, post, d) in self.token_balances if post is not None
+        ]
+
+        signatures = [
+            b58encode(hashlib.sha512(f"{self.seed}:{s}".encode()).digest()) for s in self.signers
+        ]
+        return {
+            "jsonrpc": "2.0",
+            "result": {
+                "blockTime": block_time,
+                "meta": {
+                    "computeUnitsConsumed": compute_units,
+                    "err": None,
+                    "fee": fee,
+                    "innerInstructions": inner_instructions,
+                    "loadedAddresses": {"readonly": [], "writable": []},
+                    "logMessages": logs,
+                    "postBalances": post_balances,
+                    "postTokenBalances": post_token_balances,
+                    "preBalances": pre_balances,
+                    "preTokenBalances": pre_token_balances,
+                    "rewards": [],
+                    "status": {"Ok": None},
+                },
+                "slot": slot,
+                "transaction": {
+                    "message": {
+                        "accountKeys": keys,
+                        "header": {
+                            "numReadonlySignedAccounts": 0,
+                            "numReadonlyUnsignedAccounts": readonly,
+                            "numRequiredSignatures": len(self.signers),
+                        },
+                        "instructions": [compile(p, a, d) for (p, a, d, _) in self.instructions],
+                        "recentBlockhash": key(f"{self.seed}:blockhash"),
+                    },
+                    "signatures": signatures,
+                },
+                "version": "legacy",
+            },
+            "id": 1,
+        }
+
+
+def write(name, contract, tx, slot, block_time):
+    fixture = {"token_contract": contract, "response": tx.response(slot, block_time)}
+    (FIXTURES / f"{name}.json").write_text(json.dumps(fixture, indent=2) + "\n")
+
+
+WALLET = key("wallet")
+RECIPIENT = key("recipient")
+DELEGATE = key("delegate")
+MULTISIG = key("multisig")
+SIGNER_1, SIGNER_2, SIGNER_3 = key("signer-1"), key("signer-2"), key("signer-3")
+SOURCE = key("source")
+DESTINATION = key("destination")
+
+SLOT = 245_000_000
+TIME = 1_705_000_000
+
+
+def authority(multisig):
+    """The signers of the transaction and the accounts authorizing an instruction."""
+    if multisig:
+        return [SIGNER_1, SIGNER_2], [MULTISIG, SIGNER_1, SIGNER_2]
+    return [WALLET], [WALLET]
+
+
+def kind(multisig):
+    return "multisig" if multisig else "single"
+
+
+for n, multisig in enumerate([False, True]):
+    signers, auth = authority(multisig)
+    owner = auth[0]
+    slot = SLOT + 100 * n
+
+    tx = Tx(f"transfer-{kind(multisig)}", signers)
+    tx.ix(TOKEN, [SOURCE, DESTINATION] + auth, transfer(1_500_000))
+    tx.ix(TOKEN, [SOURCE, USDC, DESTINATION] + auth, transfer_checked(250_000, 6))
+    tx.token(SOURCE, USDC, owner, 10_000_000, 8_250_000)
+    tx.token(DESTINATION, USDC, RECIPIENT, 0, 1_750_000)
+    write(f"transfer_{kind(multisig)}", USDC, tx, slot + 1, TIME + 1)
+
+    tx = Tx(f"approve-{kind(multisig)}", signers)
+    tx.ix(TOKEN, [SOURCE, DELEGATE] + auth, approve(5_000_000))
+    tx.ix(TOKEN, [SOURCE, USDC, DELEGATE] + auth, approve_checked(2_000_000, 6))
+    tx.ix(TOKEN, [SOURCE] + auth, REVOKE)
+    tx.token(SOURCE, USDC, owner, 10_000_000, 10_000_000)
+    write(f"approve_revoke_{kind(multisig)}", USDC, tx, slot + 2, TIME + 2)
+
+    tx = Tx(f"mint-to-{kind(multisig)}", signers)
+    tx.ix(TOKEN, [USDC, DESTINATION] + auth, mint_to(3_000_000))
+    tx.ix(TOKEN, [USDC, DESTINATION] + auth, mint_to_checked(7_000_000, 6))
+    tx.token(DESTINATION, USDC, RECIPIENT, 1_000_000, 11_000_000)
+    tx.balance(USDC, MINT_RENT, MINT_RENT)
+    write(f"mint_to_{kind(multisig)}", USDC, tx, slot + 3, TIME + 3)
+
+    tx = Tx(f"burn-{kind(multisig)}", signers)
+    tx.ix(TOKEN, [SOURCE, USDC] + auth, burn(400_000))
+    tx.ix(TOKEN, [SOURCE, USDC] + auth, burn_checked(600_000, 6))
+    tx.token(SOURCE, USDC, owner, 10_000_000, 9_000_000)
+    tx.balance(USDC, MINT_RENT, MINT_RENT)
+    write(f"burn_{kind(multisig)}", USDC, tx, slot + 4, TIME + 4)
+
+    new_authority = key(f"new-authority-{kind(multisig)}")
+    tx = Tx(f"set-authority-{kind(multisig)}", signers)
+    tx.ix(TOKEN, [USDC] + auth, set_authority(MINT_TOKENS, new_authority))
+    tx.ix(TOKEN, [USDC] + auth, set_authority(FREEZE_ACCOUNT_AUTHORITY, None))
+    tx.ix(TOKEN, [SOURCE] + auth, set_authority(CLOSE_ACCOUNT_AUTHORITY, new_authority))
+    tx.ix(TOKEN, [SOURCE] + auth, set_authority(ACCOUNT_OWNER, new_authority))
+    tx.token(SOURCE, USDC, new_authority, 10_000_000, 10_000_000)
+    tx.balance(USDC, MINT_RENT, MINT_RENT)
+    write(f"set_authority_{kind(multisig)}", USDC, tx, slot + 5, TIME + 5)
+
+    tx = Tx(f"freeze-thaw-{kind(multisig)}", signers)
+    tx.ix(TOKEN, [SOURCE, USDC] + auth, FREEZE_ACCOUNT)
+    tx.ix(TOKEN, [SOURCE, USDC] + auth, THAW_ACCOUNT)
+    tx.token(SOURCE, USDC, RECIPIENT, 10_000_000, 10_000_000)
+    tx.balance(USDC, MINT_RENT, MINT_RENT)
+    write(f"freeze_thaw_{kind(multisig)}", USDC, tx, slot + 6, TIME + 6)
+
+    tx = Tx(f"close-account-{kind(multisig)}", signers)
+    tx.ix(TOKEN, [SOURCE, DESTINATION] + auth, transfer(2_500_000))
+    tx.ix(TOKEN, [SOURCE, RECIPIENT] + auth, CLOSE_ACCOUNT)
+    tx.token(SOURCE, USDC, owner, 2_500_000, 0)
+    tx.token(DESTINATION, USDC, RECIPIENT, 0, 2_500_000)
+    tx.balance(SOURCE, TOKEN_ACCOUNT_RENT, 0)
+    tx.balance(RECIPIENT, 100_000_000, 100_000_000 + TOKEN_ACCOUNT_RENT)
+    write(f"close_account_{kind(multisig)}", USDC, tx, slot + 7, TIME + 7)
+
+
+# A new mint initialized along with its first accounts, through each version of the
+# instructions, the associated account being created through the Associated Token Account
+# program.
+NEW_MINT = key("new-mint")
+ACCOUNT_V1, ACCOUNT_V2 = key("account-v1"), key("account-v2")
+WALLET_ATA = key("wallet-ata")
+tx = Tx("initialize-v1", [WALLET])
+tx.ix(SYSTEM, [WALLET, NEW_MINT], system_create_account(MINT_RENT, 82, TOKEN))
+tx.ix(TOKEN, [NEW_MINT, RENT], initialize_mint(9, WALLET, WALLET))
+tx.ix(SYSTEM, [WALLET, ACCOUNT_V1], system_create_account(TOKEN_ACCOUNT_RENT, 165, TOKEN))
+tx.ix(TOKEN, [ACCOUNT_V1, NEW_MINT, WALLET, RENT], INITIALIZE_ACCOUNT)
+tx.ix(SYSTEM, [WALLET, ACCOUNT_V2], system_create_account(TOKEN_ACCOUNT_RENT, 165, TOKEN))
+tx.ix(TOKEN, [ACCOUNT_V2, NEW_MINT, RENT], initialize_account2(RECIPIENT))
+tx.balance(NEW_MINT, 0, MINT_RENT)
+tx.balance(ACCOUNT_V1, 0, TOKEN_ACCOUNT_RENT)
+tx.balance(ACCOUNT_V2, 0, TOKEN_ACCOUNT_RENT)
+tx.balance(WALLET, 1_500_000_000, 1_500_000_000 - MINT_RENT - 2 * TOKEN_ACCOUNT_RENT)
+tx.token(ACCOUNT_V1, NEW_MINT, WALLET, None, 0, 9)
+tx.token(ACCOUNT_V2, NEW_MINT, RECIPIENT, None, 0, 9)
+write("initialize_v1", NEW_MINT, tx, SLOT + 1001, TIME + 1001)
+
+tx = Tx("initialize-v2", [WALLET])
+tx.ix(SYSTEM, [WALLET, NEW_MINT], system_create_account(MINT_RENT, 82, TOKEN))
+tx.ix(TOKEN, [NEW_MINT], initialize_mint(9, WALLET, None, v2=True))
+tx.ix(ATA, [WALLET, WALLET_ATA, WALLET, NEW_MINT, SYSTEM, TOKEN], b"", inner=[
+    (TOKEN, [NEW_MINT], GET_ACCOUNT_DATA_SIZE),
+    (SYSTEM, [WALLET, WALLET_ATA], system_create_account(TOKEN_ACCOUNT_RENT, 165, TOKEN)),
+    (TOKEN, [WALLET_ATA], INITIALIZE_IMMUTABLE_OWNER),
+    (TOKEN, [WALLET_ATA, NEW_MINT], initialize_account2(WALLET, v3=True)),
+])
+tx.ix(TOKEN, [NEW_MINT, WALLET_ATA, WALLET], mint_to_checked(1_000_000_000_000, 9))
+tx.balance(NEW_MINT, 0, MINT_RENT)
+tx.balance(WALLET_ATA, 0, TOKEN_ACCOUNT_RENT)
+tx.balance(WALLET, 1_500_000_000, 1_500_000_000 - MINT_RENT - TOKEN_ACCOUNT_RENT)
+tx.token(WALLET_ATA, NEW_MINT, WALLET, None, 1_000_000_000_000, 9)
+write("initialize_v2", NEW_MINT, tx, SLOT + 1002, TIME + 1002)
+
+# A mint whose authority is a multisig, initialized through both versions of the multisig
+# instruction.
+MULTISIG_V1 = key("multisig-v1")
+tx = Tx("initialize-multisig", [SIGNER_1, SIGNER_2])
+tx.ix(SYSTEM, [SIGNER_1, MULTISIG_V1], system_create_account(MULTISIG_RENT, 355, TOKEN))
+tx.ix(TOKEN, [MULTISIG_V1, RENT, SIGNER_1, SIGNER_2, SIGNER_3], initialize_multisig(2))
+tx.ix(SYSTEM, [SIGNER_1, MULTISIG], system_create_account(MULTISIG_RENT, 355, TOKEN))
+tx.ix(TOKEN, [MULTISIG, SIGNER_1, SIGNER_2, SIGNER_3], initialize_multisig(2, v2=True))
+tx.ix(SYSTEM, [SIGNER_1, NEW_MINT], system_create_account(MINT_RENT, 82, TOKEN))
+tx.ix(TOKEN, [NEW_MINT], initialize_mint(6, MULTISIG, MULTISIG, v2=True))
+tx.ix(ATA, [SIGNER_1, WALLET_ATA, WALLET, NEW_MINT, SYSTEM, TOKEN], b"\x01", inner=[
+    (TOKEN, [NEW_MINT], GET_ACCOUNT_DATA_SIZE),
+    (SYSTEM, [SIGNER_1, WALLET_ATA], system_create_account(TOKEN_ACCOUNT_RENT, 165, TOKEN)),
+    (TOKEN, [WALLET_ATA], INITIALIZE_IMMUTABLE_OWNER),
+    (TOKEN, [WALLET_ATA, NEW_MINT], initialize_account2(WALLET, v3=True)),
+])
+tx.ix(TOKEN, [NEW_MINT, WALLET_ATA, MULTISIG, SIGNER_1, SIGNER_2], mint_to(5_000_000))
+tx.balance(MULTISIG_V1, 0, MULTISIG_RENT)
+tx.balance(MULTISIG, 0, MULTISIG_RENT)
+tx.balance(NEW_MINT, 0, MINT_RENT)
+tx.balance(WALLET_ATA, 0, TOKEN_ACCOUNT_RENT)
+tx.balance(SIGNER_1, 1_500_000_000,
+           1_500_000_000 - 2 * MULTISIG_RENT - MINT_RENT - TOKEN_ACCOUNT_RENT)
+tx.token(WALLET_ATA, NEW_MINT, WALLET, None, 5_000_000, 6)
+write("initialize_multisig", NEW_MINT, tx, SLOT + 1003, TIME + 1003)
+
+# Wrapping SOL into the associated account of the native mint, created if missing.
+WSOL_ATA = key("wallet-wsol-ata")
+tx = Tx("wrap-sol", [WALLET])
+tx.ix(ATA, [WALLET, WSOL_ATA, WALLET, WSOL, SYSTEM, TOKEN], b"\x01", inner=[
+    (TOKEN, [WSOL], GET_ACCOUNT_DATA_SIZE),
+    (SYSTEM, [WALLET, WSOL_ATA], system_create_account(TOKEN_ACCOUNT_RENT, 165, TOKEN)),
+    (TOKEN, [WSOL_ATA], INITIALIZE_IMMUTABLE_OWNER),
+    (TOKEN, [WSOL_ATA, WSOL], initialize_account2(WALLET, v3=True)),
+])
+tx.ix(SYSTEM, [WALLET, WSOL_ATA], system_transfer(500_000_000))
+tx.ix(TOKEN, [WSOL_ATA], SYNC_NATIVE)
+tx.balance(WSOL_ATA, 0, TOKEN_ACCOUNT_RENT + 500_000_000)
+tx.balance(WALLET, 1_500_000_000, 1_500_000_000 - TOKEN_ACCOUNT_RENT - 500_000_000)
+tx.balance(WSOL, 1_000_000_000, 1_000_000_000)
+tx.token(WSOL_ATA, WSOL, WALLET, None, 500_000_000, 9)
+write("wrap_sol", WSOL, tx, SLOT + 1004, TIME + 1004)
+
+# Recovering tokens sent to an associated account owned by another associated account of the
+# wallet, the nested account being emptied into the associated account of the wallet and
+# closed.
+OWNER_MINT = key("owner-mint")
+OWNER_ATA = key("owner-ata")
+NESTED_ATA = key("nested-ata")
+tx = Tx("recover-nested", [WALLET])
+tx.ix(ATA, [NESTED_ATA, USDC, WALLET_ATA, OWNER_ATA, OWNER_MINT, WALLET, TOKEN], b"\x02",
+      inner=[
+          (TOKEN, [NESTED_ATA, USDC, WALLET_ATA, OWNER_ATA], transfer_checked(42_000_000, 6)),
+          (TOKEN, [NESTED_ATA, WALLET, OWNER_ATA], CLOSE_ACCOUNT),
+      ])
+tx.balance(NESTED_ATA, TOKEN_ACCOUNT_RENT, 0)
+tx.balance(WALLET, 1_500_000_000, 1_500_000_000 + TOKEN_ACCOUNT_RENT)
+tx.balance(USDC, MINT_RENT, MINT_RENT)
+tx.balance(OWNER_MINT, MINT_RENT, MINT_RENT)
+tx.token(NESTED_ATA, USDC, OWNER_ATA, 42_000_000, 0)
+tx.token(WALLET_ATA, USDC, WALLET, 8_000_000, 50_000_000)
+tx.token(OWNER_ATA, OWNER_MINT, WALLET, 1, 1, 0)
+write("recover_nested", USDC, tx, SLOT + 1005, TIME + 1005)
@@ -0,0 +1,57 @@
+#!/usr/bin/env python3
+"""Records a fixture of tests/decoding.rs from the getTransaction response of a transaction.
+
+The fixture holds the response as returned by the RPC, along with the token contract whose
+events are decoded. Its golden file is then written by running the tests with UPDATE_GOLDEN=1,
+and both are reviewed before committing them.
+
+    python3 scripts/record_fixture.py <name> <signature> <token contract>  # from the cli directory
+
+The RPC_URL environment variable sets the endpoint, mainnet-beta by default.
+"""
+
+import json
+import os
+import sys
+import urllib.request
+from pathlib import Path
+
+FIXTURES = Path(__file__).resolve().parent.parent / "tests/fixtures"
+RPC_URL = os.environ.get("RPC_URL", "https://api.mainnet-beta.solana.com")
+
+
+def get_transaction(signature):
+    request = {
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "getTransaction",
+        "params": [signature, {"encoding": "json", "maxSupportedTransactionVersion": 0}],
+    }
+    request = urllib.request.Request(
+        RPC_URL,
+        data=json.dumps(request).encode(),
+        headers={"Content-Type": "application/json"},
+    )
+    with urllib.request.urlopen(request) as response:
+        response = json.load(response)
+
+    if "error" in response:
+        raise SystemExit(f"getTransaction failed: {response['error']}")
+    if response.get("result") is None:
+        raise SystemExit(f"transaction {signature} not found")
+    return response
+
+
+def main():
+    if len(sys.argv) != 4:
+        raise SystemExit(__doc__)
+    name, signature, token_contract = sys.argv[1:]
+
+    fixture = {"token_contract": token_contract, "response": get_transaction(signature)}
+    path = FIXTURES / f"{name}.json"
+    path.write_text(json.dumps(fixture, indent=2) + "\n")
+    print(f"recorded {path}, run UPDATE_GOLDEN=1 cargo test --test decoding to write its golden")
+
+
+if __name__ == "__main__":
+    main()
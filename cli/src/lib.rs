@@ -0,0 +1,36 @@
+//! Decodes a single Solana transaction offline into the `Events` that `map_block` produces for
+//! it.
+
+pub mod transaction;
+
+use anyhow::Result;
+use substreams_solana_spl_token::{
+    pb::{
+        sf::solana::spl::token::v1::{BlockMeta, Events},
+        sol::transactions::v1::Transactions,
+    },
+    to_events, Filter,
+};
+
+use crate::transaction::Input;
+
+/// Runs `to_events` on the transaction as `map_block` would, without multisig accounts to
+/// resolve the signers with. The index of the transaction in its block isn't known and is
+/// reported as 0.
+pub fn decode(filter: &Filter, input: Input) -> Result<Events> {
+    // A transaction only tells the slot and time of its block, the other fields are left unset.
+    let block = BlockMeta {
+        slot: input.slot,
+        block_timestamp: input.block_time.unwrap_or_default(),
+        ..Default::default()
+    };
+
+    to_events(
+        filter,
+        &block,
+        Transactions {
+            transactions: vec![input.transaction],
+        },
+        |_| None,
+    )
+}
@@ -0,0 +1,74 @@
+//! Decodes a single Solana transaction offline and prints, as JSON, the `Events` that
+//! `map_block` produces for it.
+
+use std::{
+    fs,
+    io::{self, Read, Write},
+};
+
+use anyhow::{anyhow, bail, Result};
+use substreams_solana_spl_token::Filter;
+use substreams_solana_spl_token_cli::{decode, transaction};
+
+const USAGE: &str = "\
+Usage: spl-token-decode --token-contract <address> [--raw-instructions] [FILE]
+
+Prints the events of the transaction for the token contract, as map_block would. FILE, or the
+standard input when omitted, holds either a getTransaction JSON response, with the json or
+base64 encoding, or a base58 or base64 serialized transaction. A serialized transaction has no
+balances nor inner instructions, and multisig signers are never resolved.";
+
+struct Args {
+    filter: Filter,
+    path: Option<String>,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self> {
+        let mut filter = Filter::default();
+        let mut path = None;
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--token-contract" => {
+                    filter.token_contract = args
+                        .next()
+                        .ok_or_else(|| anyhow!("--token-contract requires an address"))?;
+                }
+                "--raw-instructions" => filter.raw_instructions = true,
+                "-h" | "--help" => {
+                    println!("{USAGE}");
+                    std::process::exit(0);
+                }
+                _ if arg.starts_with('-') => bail!("unknown option {arg}\n\n{USAGE}"),
+                _ if path.is_none() => path = Some(arg),
+                _ => bail!("unexpected argument {arg}\n\n{USAGE}"),
+            }
+        }
+
+        if filter.token_contract.is_empty() {
+            bail!("--token-contract is required\n\n{USAGE}");
+        }
+
+        Ok(Args { filter, path })
+    }
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse(std::env::args().skip(1))?;
+
+    let input = match &args.path {
+        Some(path) => fs::read_to_string(path)?,
+        None => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input)?;
+            input
+        }
+    };
+    let events = decode(&args.filter, transaction::parse(&input)?)?;
+
+    let mut stdout = io::stdout().lock();
+    serde_json::to_writer_pretty(&mut stdout, &events)?;
+    writeln!(stdout)?;
+
+    Ok(())
+}
@@ -0,0 +1,518 @@
+//! Reads a transaction, either serialized in the Solana wire format or as returned by the
+//! `getTransaction` RPC method, into the `ConfirmedTransaction` consumed by `map_block`.
+
+use anyhow::{anyhow, bail, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::Deserialize;
+use solana_sdk::{message::VersionedMessage, transaction::VersionedTransaction};
+use substreams_solana::pb::sf::solana::r#type::v1::{
+    CompiledInstruction, ConfirmedTransaction, InnerInstruction, InnerInstructions, Message,
+    MessageAddressTableLookup, MessageHeader, TokenBalance, Transaction, TransactionError,
+    TransactionStatusMeta, UiTokenAmount,
+};
+
+/// A transaction along with the slot and time of its block, when known.
+pub struct Input {
+    pub transaction: ConfirmedTransaction,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+}
+
+/// Parses a `getTransaction` JSON response, or its `result` alone, and otherwise a base58 or
+/// base64 serialized transaction. A serialized transaction comes without its status meta, so
+/// it has no balances nor inner instructions.
+pub fn parse(input: &str) -> Result<Input> {
+    let input = input.trim();
+    if input.starts_with('{') {
+        return from_rpc_response(input);
+    }
+
+    let transaction = bs58::decode(input)
+        .into_vec()
+        .map_err(|err| anyhow!(err))
+        .and_then(|bytes| from_wire(&bytes))
+        .or_else(|_| -> Result<ConfirmedTransaction> { from_wire(&STANDARD.decode(input)?) })
+        .context("input is neither a JSON response nor a base58 or base64 transaction")?;
+
+    let message = transaction
+        .transaction
+        .as_ref()
+        .unwrap()
+        .message
+        .as_ref()
+        .unwrap();
+    if !message.address_table_lookups.is_empty() {
+        bail!(
+            "the transaction loads accounts from address lookup tables, which can't be resolved \
+             offline, use a getTransaction response instead"
+        );
+    }
+
+    Ok(Input {
+        transaction,
+        slot: 0,
+        block_time: None,
+    })
+}
+
+/// Reads the Solana wire format, i.e. the signatures followed by the legacy or v0 message.
+fn from_wire(bytes: &[u8]) -> Result<ConfirmedTransaction> {
+    let transaction: VersionedTransaction = bincode::deserialize(bytes)?;
+    transaction.sanitize()?;
+
+    let message = &transaction.message;
+    let header = message.header();
+
+    Ok(ConfirmedTransaction {
+        transaction: Some(Transaction {
+            signatures: transaction
+                .signatures
+                .iter()
+                .map(|signature| signature.as_ref().to_vec())
+                .collect(),
+            message: Some(Message {
+                header: Some(MessageHeader {
+                    num_required_signatures: header.num_required_signatures as u32,
+                    num_readonly_signed_accounts: header.num_readonly_signed_accounts as u32,
+                    num_readonly_unsigned_accounts: header.num_readonly_unsigned_accounts as u32,
+                }),
+                account_keys: message
+                    .static_account_keys()
+                    .iter()
+                    .map(|key| key.to_bytes().to_vec())
+                    .collect(),
+                recent_blockhash: message.recent_blockhash().to_bytes().to_vec(),
+                instructions: message
+                    .instructions()
+                    .iter()
+                    .map(|instruction| CompiledInstruction {
+                        program_id_index: instruction.program_id_index as u32,
+                        accounts: instruction.accounts.clone(),
+                        data: instruction.data.clone(),
+                    })
+                    .collect(),
+                versioned: !matches!(message, VersionedMessage::Legacy(_)),
+                address_table_lookups: message
+                    .address_table_lookups()
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|lookup| MessageAddressTableLookup {
+                        account_key: lookup.account_key.to_bytes().to_vec(),
+                        writable_indexes: lookup.writable_indexes.clone(),
+                        readonly_indexes: lookup.readonly_indexes.clone(),
+                    })
+                    .collect(),
+            }),
+        }),
+        meta: Some(TransactionStatusMeta::default()),
+    })
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RpcTransaction {
+    slot: u64,
+    block_time: Option<i64>,
+    transaction: RpcEncodedTransaction,
+    meta: Option<RpcMeta>,
+}
+
+/// The transaction of the response, `[data, encoding]` for the binary encodings.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RpcEncodedTransaction {
+    Binary(String, String),
+    Json(RpcJsonTransaction),
+}
+
+#[derive(Deserialize)]
+struct RpcJsonTransaction {
+    signatures: Vec<String>,
+    message: RpcMessage,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RpcMessage {
+    header: RpcMessageHeader,
+    account_keys: Vec<String>,
+    recent_blockhash: String,
+    instructions: Vec<RpcInstruction>,
+    address_table_lookups: Option<Vec<RpcAddressTableLookup>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RpcMessageHeader {
+    num_required_signatures: u32,
+    num_readonly_signed_accounts: u32,
+    num_readonly_unsigned_accounts: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RpcInstruction {
+    program_id_index: u32,
+    accounts: Vec<u8>,
+    data: String,
+    stack_height: Option<u32>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RpcAddressTableLookup {
+    account_key: String,
+    writable_indexes: Vec<u8>,
+    readonly_indexes: Vec<u8>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RpcMeta {
+    err: Option<serde_json::Value>,
+    fee: u64,
+    pre_balances: Vec<u64>,
+    post_balances: Vec<u64>,
+    inner_instructions: Option<Vec<RpcInnerInstructions>>,
+    log_messages: Option<Vec<String>>,
+    pre_token_balances: Option<Vec<RpcTokenBalance>>,
+    post_token_balances: Option<Vec<RpcTokenBalance>>,
+    loaded_addresses: Option<RpcLoadedAddresses>,
+    compute_units_consumed: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct RpcInnerInstructions {
+    index: u32,
+    instructions: Vec<RpcInstruction>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RpcTokenBalance {
+    account_index: u32,
+    mint: String,
+    owner: Option<String>,
+    program_id: Option<String>,
+    ui_token_amount: RpcUiTokenAmount,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RpcUiTokenAmount {
+    amount: String,
+    decimals: u32,
+    ui_amount: Option<f64>,
+    ui_amount_string: String,
+}
+
+#[derive(Deserialize)]
+struct RpcLoadedAddresses {
+    writable: Vec<String>,
+    readonly: Vec<String>,
+}
+
+fn from_rpc_response(input: &str) -> Result<Input> {
+    let mut response: serde_json::Value = serde_json::from_str(input)?;
+    if let Some(result) = response.get_mut("result") {
+        response = result.take();
+    }
+    if response.is_null() {
+        bail!("the response has no transaction");
+    }
+
+    let rpc: RpcTransaction = serde_json::from_value(response)
+        .context("unsupported getTransaction response, use the json or base64 encoding")?;
+
+    let mut transaction = match rpc.transaction {
+        RpcEncodedTransaction::Binary(data, encoding) => from_wire(&match encoding.as_str() {
+            "base64" => STANDARD.decode(&data)?,
+            "base58" => bs58::decode(&data).into_vec()?,
+            _ => bail!("unsupported transaction encoding {encoding}"),
+        })?,
+        RpcEncodedTransaction::Json(json) => from_rpc_transaction(json)?,
+    };
+    // map_block skips the transactions without meta, which would print no events
+    let meta = rpc
+        .meta
+        .ok_or_else(|| anyhow!("the response has no transaction meta"))?;
+    transaction.meta = Some(from_rpc_meta(meta)?);
+
+    Ok(Input {
+        transaction,
+        slot: rpc.slot,
+        block_time: rpc.block_time,
+    })
+}
+
+fn from_rpc_transaction(json: RpcJsonTransaction) -> Result<ConfirmedTransaction> {
+    let message = json.message;
+    let address_table_lookups = message
+        .address_table_lookups
+        .map(|lookups| {
+            lookups
+                .into_iter()
+                .map(|lookup| -> Result<MessageAddressTableLookup> {
+                    Ok(MessageAddressTableLookup {
+                        account_key: decode_base58(&lookup.account_key)?,
+                        writable_indexes: lookup.writable_indexes,
+                        readonly_indexes: lookup.readonly_indexes,
+                    })
+                })
+                .collect::<Result<Vec<_>>>()
+        })
+        .transpose()?;
+
+    Ok(ConfirmedTransaction {
+        transaction: Some(Transaction {
+            signatures: json
+                .signatures
+                .iter()
+                .map(|signature| decode_base58(signature))
+                .collect::<Result<_>>()?,
+            message: Some(Message {
+                header: Some(MessageHeader {
+                    num_required_signatures: message.header.num_required_signatures,
+                    num_readonly_signed_accounts: message.header.num_readonly_signed_accounts,
+                    num_readonly_unsigned_accounts: message.header.num_readonly_unsigned_accounts,
+                }),
+                account_keys: message
+                    .account_keys
+                    .iter()
+                    .map(|key| decode_base58(key))
+                    .collect::<Result<_>>()?,
+                recent_blockhash: decode_base58(&message.recent_blockhash)?,
+                instructions: message
+                    .instructions
+                    .into_iter()
+                    .map(|instruction| -> Result<CompiledInstruction> {
+                        Ok(CompiledInstruction {
+                            program_id_index: instruction.program_id_index,
+                            accounts: instruction.accounts,
+                            data: decode_base58(&instruction.data)?,
+                        })
+                    })
+                    .collect::<Result<_>>()?,
+                versioned: address_table_lookups.is_some(),
+                address_table_lookups: address_table_lookups.unwrap_or_default(),
+            }),
+        }),
+        meta: None,
+    })
+}
+
+fn from_rpc_meta(meta: RpcMeta) -> Result<TransactionStatusMeta> {
+    let loaded_addresses = meta.loaded_addresses.unwrap_or(RpcLoadedAddresses {
+        writable: vec![],
+        readonly: vec![],
+    });
+
+    Ok(TransactionStatusMeta {
+        err: meta.err.map(|err| TransactionError {
+            err: err.to_string().into_bytes(),
+        }),
+        fee: meta.fee,
+        pre_balances: meta.pre_balances,
+        post_balances: meta.post_balances,
+        inner_instructions: meta
+            .inner_instructions
+            .unwrap_or_default()
+            .into_iter()
+            .map(|inner| -> Result<InnerInstructions> {
+                Ok(InnerInstructions {
+                    index: inner.index,
+                    instructions: inner
+                        .instructions
+                        .into_iter()
+                        .map(|instruction| -> Result<InnerInstruction> {
+                            Ok(InnerInstruction {
+                                program_id_index: instruction.program_id_index,
+                                accounts: instruction.accounts,
+                                data: decode_base58(&instruction.data)?,
+                                stack_height: instruction.stack_height,
+                            })
+                        })
+                        .collect::<Result<_>>()?,
+                })
+            })
+            .collect::<Result<_>>()?,
+        log_messages: meta.log_messages.unwrap_or_default(),
+        pre_token_balances: meta
+            .pre_token_balances
+            .unwrap_or_default()
+            .into_iter()
+            .map(from_rpc_token_balance)
+            .collect(),
+        post_token_balances: meta
+            .post_token_balances
+            .unwrap_or_default()
+            .into_iter()
+            .map(from_rpc_token_balance)
+            .collect(),
+        loaded_writable_addresses: loaded_addresses
+            .writable
+            .iter()
+            .map(|key| decode_base58(key))
+            .collect::<Result<_>>()?,
+        loaded_readonly_addresses: loaded_addresses
+            .readonly
+            .iter()
+            .map(|key| decode_base58(key))
+            .collect::<Result<_>>()?,
+        compute_units_consumed: meta.compute_units_consumed,
+        ..Default::default()
+    })
+}
+
+fn from_rpc_token_balance(balance: RpcTokenBalance) -> TokenBalance {
+    TokenBalance {
+        account_index: balance.account_index,
+        mint: balance.mint,
+        owner: balance.owner.unwrap_or_default(),
+        program_id: balance.program_id.unwrap_or_default(),
+        ui_token_amount: Some(UiTokenAmount {
+            ui_amount: balance.ui_token_amount.ui_amount.unwrap_or_default(),
+            decimals: balance.ui_token_amount.decimals,
+            amount: balance.ui_token_amount.amount,
+            ui_amount_string: balance.ui_token_amount.ui_amount_string,
+        }),
+    }
+}
+
+fn decode_base58(value: &str) -> Result<Vec<u8>> {
+    bs58::decode(value)
+        .into_vec()
+        .with_context(|| format!("invalid base58 {value:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A legacy transaction with one signature, two account keys and one instruction of the
+    /// second account invoked with the first one.
+    fn wire_transaction() -> Vec<u8> {
+        let mut bytes = vec![1];
+        bytes.extend([7; 64]);
+        bytes.extend([1, 0, 1]);
+        bytes.push(2);
+        bytes.extend([1; 32]);
+        bytes.extend([2; 32]);
+        bytes.extend([3; 32]);
+        bytes.extend([1, 1, 1, 0, 2, 3, 4]);
+        bytes
+    }
+
+    #[test]
+    fn test_parse_serialized() {
+        let bytes = wire_transaction();
+
+        for input in [bs58::encode(&bytes).into_string(), STANDARD.encode(&bytes)] {
+            let transaction = parse(&input).unwrap().transaction;
+            assert_eq!(transaction.id(), bs58::encode([7u8; 64]).into_string());
+
+            let message = transaction.transaction.unwrap().message.unwrap();
+            assert_eq!(message.header.unwrap().num_required_signatures, 1);
+            assert_eq!(message.account_keys, vec![vec![1; 32], vec![2; 32]]);
+            assert_eq!(message.recent_blockhash, vec![3; 32]);
+            assert_eq!(
+                message.instructions,
+                vec![CompiledInstruction {
+                    program_id_index: 1,
+                    accounts: vec![0],
+                    data: vec![3, 4],
+                }]
+            );
+            assert!(!message.versioned);
+        }
+
+        let mut truncated = wire_transaction();
+        truncated.pop();
+        assert!(parse(&STANDARD.encode(truncated)).is_err());
+    }
+
+    #[test]
+    fn test_parse_rpc_response() {
+        let response = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": {
+                "slot": 42,
+                "blockTime": 1_700_000_000,
+                "transaction": [STANDARD.encode(wire_transaction()), "base64"],
+                "meta": {
+                    "err": null,
+                    "fee": 5000,
+                    "preBalances": [10, 1],
+                    "postBalances": [5, 1],
+                    "innerInstructions": [{
+                        "index": 0,
+                        "instructions": [{
+                            "programIdIndex": 1,
+                            "accounts": [0],
+                            "data": bs58::encode([5u8]).into_string(),
+                            "stackHeight": 2
+                        }]
+                    }],
+                    "preTokenBalances": [{
+                        "accountIndex": 0,
+                        "mint": "mint",
+                        "owner": "owner",
+                        "uiTokenAmount": {
+                            "amount": "100",
+                            "decimals": 2,
+                            "uiAmount": 1.0,
+                            "uiAmountString": "1"
+                        }
+                    }],
+                    "postTokenBalances": [],
+                    "computeUnitsConsumed": 150
+                }
+            }
+        });
+
+        let input = parse(&response.to_string()).unwrap();
+        assert_eq!(input.slot, 42);
+        assert_eq!(input.block_time, Some(1_700_000_000));
+
+        let meta = input.transaction.meta.unwrap();
+        assert_eq!(meta.err, None);
+        assert_eq!(meta.fee, 5000);
+        assert_eq!(meta.inner_instructions[0].instructions[0].data, vec![5]);
+        assert_eq!(
+            meta.inner_instructions[0].instructions[0].stack_height,
+            Some(2)
+        );
+        assert_eq!(meta.pre_token_balances[0].owner, "owner");
+        assert_eq!(
+            meta.pre_token_balances[0]
+                .ui_token_amount
+                .as_ref()
+                .unwrap()
+                .amount,
+            "100"
+        );
+        assert_eq!(meta.compute_units_consumed, Some(150));
+    }
+
+    #[test]
+    fn test_parse_rpc_response_without_transaction() {
+        assert!(parse(r#"{"jsonrpc":"2.0","id":1,"result":null}"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_rpc_response_without_meta() {
+        let response = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": {
+                "slot": 42,
+                "blockTime": null,
+                "transaction": [STANDARD.encode(wire_transaction()), "base64"],
+                "meta": null
+            }
+        });
+
+        assert!(parse(&response.to_string()).is_err());
+    }
+}
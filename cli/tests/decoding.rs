@@ -0,0 +1,121 @@
+//! Decodes the transactions of `tests/fixtures` with `spl-token-decode` and compares
+//! the resulting events with the golden file next to each fixture. A fixture is recorded with
+//! `scripts/record_fixture.py`, and its golden file is only written, like after an intended
+//! change of the decoding, by `UPDATE_GOLDEN=1 cargo test --test decoding`, the diff of the
+//! golden files being reviewed before committing them.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+use substreams_solana_spl_token::{pb::sf::solana::spl::token::v1::Events, Filter};
+use substreams_solana_spl_token_cli::{decode, transaction};
+
+/// A `getTransaction` response, as returned by the RPC, along with the token contract whose
+/// events are decoded.
+#[derive(Deserialize)]
+struct Fixture {
+    token_contract: String,
+    response: serde_json::Value,
+}
+
+fn fixtures_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+fn check_fixture(path: &Path) -> Result<(), String> {
+    let name = path.file_stem().unwrap().to_string_lossy();
+    let fixture: Fixture = serde_json::from_str(&fs::read_to_string(path).unwrap())
+        .map_err(|err| format!("fixture {name} can't be read: {err}"))?;
+
+    let filter = Filter {
+        token_contract: fixture.token_contract,
+        ..Default::default()
+    };
+    let input = transaction::parse(&fixture.response.to_string())
+        .map_err(|err| format!("fixture {name} can't be parsed: {err:#}"))?;
+    let events = decode(&filter, input)
+        .map_err(|err| format!("fixture {name} can't be decoded: {err:#}"))?;
+    if events.data.is_empty() {
+        return Err(format!("no event decoded from fixture {name}"));
+    }
+    check_json(&name, &events)?;
+
+    let actual = serde_json::to_value(&events).unwrap();
+    let golden = path.with_extension("golden.json");
+    if env::var_os("UPDATE_GOLDEN").is_some() {
+        let mut json = serde_json::to_string_pretty(&actual).unwrap();
+        json.push('\n');
+        fs::write(&golden, json).unwrap();
+        return Ok(());
+    }
+
+    let expected = fs::read_to_string(&golden).map_err(|err| {
+        format!(
+            "golden file {} of fixture {name} can't be read ({err}), run with UPDATE_GOLDEN=1 \
+             to record it and review it before committing",
+            golden.display(),
+        )
+    })?;
+    let expected: serde_json::Value = serde_json::from_str(&expected).unwrap();
+    if expected != actual {
+        return Err(format!(
+            "events decoded from fixture {name} differ from {}, run with UPDATE_GOLDEN=1 if the \
+             change is intended\n--- expected\n{:#}\n--- actual\n{:#}",
+            golden.display(),
+            expected,
+            actual,
+        ));
+    }
+
+    Ok(())
+}
+
+/// Checks that the JSON of the events matches `events.schema.json` and reads back into the
+/// same events.
+fn check_json(name: &str, events: &Events) -> Result<(), String> {
+    let schema = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../tokens/events.schema.json");
+    let schema: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(schema).unwrap()).unwrap();
+    let schema = jsonschema::JSONSchema::compile(&schema).unwrap();
+
+    let json = serde_json::to_value(events).unwrap();
+    if let Err(errors) = schema.validate(&json) {
+        let errors: Vec<_> = errors
+            .map(|error| format!("{} at {}", error, error.instance_path))
+            .collect();
+        return Err(format!(
+            "events decoded from fixture {name} don't match the JSON schema:\n{}",
+            errors.join("\n")
+        ));
+    }
+
+    let read_back: Events = serde_json::from_value(json).unwrap();
+    if &read_back != events {
+        return Err(format!("JSON of fixture {name} doesn't read back"));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_fixtures() {
+    let mut fixtures: Vec<_> = fs::read_dir(fixtures_dir())
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            let name = path.file_name().unwrap().to_string_lossy();
+            name.ends_with(".json") && !name.ends_with(".golden.json")
+        })
+        .collect();
+    fixtures.sort();
+    assert!(!fixtures.is_empty(), "no fixture to decode");
+
+    let failures: Vec<_> = fixtures
+        .iter()
+        .filter_map(|path| check_fixture(path).err())
+        .collect();
+    assert!(failures.is_empty(), "{}", failures.join("\n\n"));
+}
@@ -1,11 +1,216 @@
+-- Event tables are append-only and deduplicated by their sorting key, the slot being the
+-- version so that a transaction re-included in a later block after a reorg wins. The block
+-- height is the number of blocks before the block, which lags behind the slot as slots can be
+-- skipped. State tables are deduplicated by their `id`, the `version` being the slot of the
+-- write. A newer row replaces the whole older one, so `db_out` always writes state rows
+-- complete, from the state kept in its stores.
+
 CREATE TABLE IF NOT EXISTS transfer (
-    "amount" String,
+    "amount" UInt64,
     "source" String,
     "destination" String,
+    "memo" String,
     "signer" String,
+    "multisig_account" String,
+    "signers" Array(String),
     "evt_tx" String,
-    "evt_block_timestamp" TIMESTAMP,
+    "evt_block_timestamp" DateTime('UTC'),
+    "evt_slot" UInt64,
     "evt_block_height" UInt64,
     "evt_block_hash" String,
+    "evt_parent_slot" UInt64,
+    "evt_previous_block_hash" String,
+    "evt_instruction_index" UInt32,
+    "evt_outer_instruction_index" UInt32,
+    "evt_inner_instruction_index" UInt32,
+    "evt_stack_height" UInt32,
+    "evt_parent_program_id" String,
+    "evt_instruction_kind" LowCardinality(String),
+    INDEX idx_destination ("destination") TYPE bloom_filter GRANULARITY 4
+) ENGINE = ReplacingMergeTree("evt_slot")
+PARTITION BY toYYYYMM("evt_block_timestamp")
+ORDER BY ("source","evt_tx","evt_instruction_index");
+
+CREATE TABLE IF NOT EXISTS associated_account_created (
+    "idempotent" Bool,
+    "payer" String,
+    "associated_account" String,
+    "wallet" String,
+    "mint" String,
+    "token_program" String,
+    "evt_tx" String,
+    "evt_block_timestamp" DateTime('UTC'),
+    "evt_slot" UInt64,
+    "evt_block_height" UInt64,
+    "evt_block_hash" String,
+    "evt_parent_slot" UInt64,
+    "evt_previous_block_hash" String,
+    "evt_instruction_index" UInt32,
+    "evt_outer_instruction_index" UInt32,
+    "evt_inner_instruction_index" UInt32,
+    "evt_stack_height" UInt32,
+    "evt_parent_program_id" String,
+    "evt_instruction_kind" LowCardinality(String)
+) ENGINE = ReplacingMergeTree("evt_slot")
+PARTITION BY toYYYYMM("evt_block_timestamp")
+ORDER BY ("wallet","evt_tx","evt_instruction_index");
+
+CREATE TABLE IF NOT EXISTS close_account (
+    "account" String,
+    "destination" String,
+    "mint" String,
+    "amount" UInt64,
+    "reclaimed_lamports" UInt64,
+    "signer" String,
+    "multisig_account" String,
+    "signers" Array(String),
+    "evt_tx" String,
+    "evt_block_timestamp" DateTime('UTC'),
+    "evt_slot" UInt64,
+    "evt_block_height" UInt64,
+    "evt_block_hash" String,
+    "evt_parent_slot" UInt64,
+    "evt_previous_block_hash" String,
+    "evt_instruction_index" UInt32,
+    "evt_outer_instruction_index" UInt32,
+    "evt_inner_instruction_index" UInt32,
+    "evt_stack_height" UInt32,
+    "evt_parent_program_id" String,
+    "evt_instruction_kind" LowCardinality(String)
+) ENGINE = ReplacingMergeTree("evt_slot")
+PARTITION BY toYYYYMM("evt_block_timestamp")
+ORDER BY ("destination","evt_tx","evt_instruction_index");
+
+-- The initial authorities of mints and every later change of an authority. `renounced` is set
+-- when the mint tokens or freeze authority of a mint is removed.
+CREATE TABLE IF NOT EXISTS authority_changes (
+    "account" String,
+    "account_kind" LowCardinality(String),
+    "authority_type" LowCardinality(String),
+    "old_authority" Nullable(String),
+    "new_authority" Nullable(String),
+    "renounced" Bool,
+    "evt_tx" String,
+    "evt_block_timestamp" DateTime('UTC'),
+    "evt_slot" UInt64,
+    "evt_block_height" UInt64,
+    "evt_block_hash" String,
+    "evt_parent_slot" UInt64,
+    "evt_previous_block_hash" String,
     "evt_instruction_index" UInt32
-) ENGINE = MergeTree PRIMARY KEY ("evt_tx","evt_instruction_index");
+) ENGINE = ReplacingMergeTree("evt_slot")
+PARTITION BY toYYYYMM("evt_block_timestamp")
+ORDER BY ("account","authority_type","evt_tx","evt_instruction_index");
+
+CREATE TABLE IF NOT EXISTS instruction_signers (
+    "signer" String,
+    "multisig_account" String,
+    "evt_table" String,
+    "evt_tx" String,
+    "evt_block_timestamp" DateTime('UTC'),
+    "evt_slot" UInt64,
+    "evt_block_height" UInt64,
+    "evt_block_hash" String,
+    "evt_parent_slot" UInt64,
+    "evt_previous_block_hash" String,
+    "evt_instruction_index" UInt32
+) ENGINE = ReplacingMergeTree("evt_slot")
+PARTITION BY toYYYYMM("evt_block_timestamp")
+ORDER BY ("signer","evt_tx","evt_instruction_index");
+
+CREATE TABLE IF NOT EXISTS transactions (
+    "id" String,
+    "index" UInt32,
+    "fee_payer" String,
+    "signers" Array(String),
+    "fee" UInt64,
+    "compute_units_consumed" Nullable(UInt64),
+    "recent_blockhash" String,
+    "success" Bool,
+    "evt_block_timestamp" DateTime('UTC'),
+    "evt_slot" UInt64,
+    "evt_block_height" UInt64,
+    "evt_block_hash" String,
+    "evt_parent_slot" UInt64,
+    "evt_previous_block_hash" String
+) ENGINE = ReplacingMergeTree("evt_slot")
+PARTITION BY toYYYYMM("evt_block_timestamp")
+ORDER BY ("id");
+
+CREATE TABLE IF NOT EXISTS token_accounts (
+    "id" String,
+    "owner" String,
+    "mint" String,
+    "delegate" String,
+    "delegated_amount" UInt64,
+    "close_authority" String,
+    "is_associated_account" Bool,
+    "frozen" Bool,
+    "closed" Bool,
+    "version" UInt64
+) ENGINE = ReplacingMergeTree("version")
+ORDER BY ("id");
+
+-- The accounts frozen or thawed since the start block, query them with `WHERE "frozen"` to
+-- list the accounts currently frozen. `freeze_authority` is the authority of the last
+-- freeze or thaw.
+CREATE TABLE IF NOT EXISTS frozen_accounts (
+    "id" String,
+    "mint" String,
+    "frozen" Bool,
+    "freeze_authority" String,
+    "version" UInt64
+) ENGINE = ReplacingMergeTree("version")
+ORDER BY ("id");
+
+CREATE TABLE IF NOT EXISTS mints (
+    "id" String,
+    "supply" String,
+    "decimals" UInt32,
+    "mint_authority" String,
+    "freeze_authority" String,
+    "version" UInt64
+) ENGINE = ReplacingMergeTree("version")
+ORDER BY ("id");
+
+CREATE TABLE IF NOT EXISTS balances (
+    "id" String,
+    "mint" String,
+    "owner" String,
+    "amount" UInt64,
+    "decimals" UInt32,
+    "evt_tx" String,
+    "version" UInt64
+) ENGINE = ReplacingMergeTree("version")
+ORDER BY ("id");
+
+-- The mint statistics are cumulative within their period and each insert only carries the
+-- metrics that changed in the block, so the columns are merged with `max` rather than
+-- replaced, the metrics missing from an insert defaulting to 0. Query them with `GROUP BY "mint", "period_start"` and `max(...)` or `FINAL`.
+CREATE TABLE IF NOT EXISTS mint_stats_hourly (
+    "mint" String,
+    "period_start" DateTime('UTC'),
+    "transfer_count" SimpleAggregateFunction(max, UInt64),
+    "transfer_volume" SimpleAggregateFunction(max, UInt256),
+    "mint_volume" SimpleAggregateFunction(max, UInt256),
+    "burn_volume" SimpleAggregateFunction(max, UInt256),
+    "unique_senders" SimpleAggregateFunction(max, UInt64),
+    "unique_receivers" SimpleAggregateFunction(max, UInt64),
+    "version" SimpleAggregateFunction(max, UInt64)
+) ENGINE = AggregatingMergeTree
+PARTITION BY toYYYYMM("period_start")
+ORDER BY ("mint","period_start");
+
+CREATE TABLE IF NOT EXISTS mint_stats_daily (
+    "mint" String,
+    "period_start" DateTime('UTC'),
+    "transfer_count" SimpleAggregateFunction(max, UInt64),
+    "transfer_volume" SimpleAggregateFunction(max, UInt256),
+    "mint_volume" SimpleAggregateFunction(max, UInt256),
+    "burn_volume" SimpleAggregateFunction(max, UInt256),
+    "unique_senders" SimpleAggregateFunction(max, UInt64),
+    "unique_receivers" SimpleAggregateFunction(max, UInt64),
+    "version" SimpleAggregateFunction(max, UInt64)
+) ENGINE = AggregatingMergeTree
+PARTITION BY toYYYYMM("period_start")
+ORDER BY ("mint","period_start");
@@ -0,0 +1,318 @@
+#!/usr/bin/env python3
+"""Generates events.schema.json, the JSON Schema of the 'serde' feature of the pb types.
+
+The JSON shape of a field follows from its proto type and from the serde attributes that
+buf.gen.yaml attaches to the generated types, so that the schema can't drift from the
+generated code: a 64-bit integer, bytes or enum field without its attribute is an error.
+
+    python3 scripts/generate_schema.py  # from the tokens directory, or `make schema`
+"""
+
+import json
+import re
+import sys
+from pathlib import Path
+
+ROOT = Path(__file__).resolve().parent.parent
+PROTOS = [
+    ROOT / "proto/sf/solana/spl/token/v1/spl_token.proto",
+    ROOT / "proto/sol/instructions/v1/instructions.proto",
+]
+BUF_GEN = ROOT / "buf.gen.yaml"
+SCHEMA = ROOT / "events.schema.json"
+
+# The messages described by the schema, along with the ones they refer to
+ROOT_MESSAGE = ".sf.solana.spl.token.v1.Events"
+OTHER_MESSAGES = [
+    ".sf.solana.spl.token.v1.TokenTransactions",
+    ".sf.solana.spl.token.v1.BlockMeta",
+]
+
+UINT32 = {"type": "integer", "minimum": 0, "maximum": 4294967295}
+INT32 = {"type": "integer", "minimum": -2147483648, "maximum": 2147483647}
+SCALARS = {
+    "string": {"type": "string"},
+    "bool": {"type": "boolean"},
+    "uint32": UINT32,
+    "fixed32": UINT32,
+    "int32": INT32,
+    "sint32": INT32,
+    "sfixed32": INT32,
+    "float": {"type": "number"},
+    "double": {"type": "number"},
+}
+# The JSON of the fields serialized by the modules of src/json.rs
+WITH_MODULES = {
+    "u64_string": {"$ref": "#/definitions/uint64"},
+    "i64_string": {"$ref": "#/definitions/int64"},
+    "option_u64_string": {"oneOf": [{"$ref": "#/definitions/uint64"}, {"type": "null"}]},
+    "base58": {"type": "string", "description": "Base58 encoded bytes"},
+}
+STRINGIFIED = {"uint64", "int64", "fixed64", "sfixed64", "sint64", "bytes"}
+
+TOKEN = re.compile(r'//[^\n]*|"[^"]*"|[A-Za-z_][\w.]*|\d+|\S')
+
+
+class Message:
+    def __init__(self, name, comment):
+        self.name = name
+        self.comment = comment
+        self.fields = []
+        self.oneofs = []
+
+
+def parse(path, messages, enums):
+    """Reads the messages and enums of a proto file, keyed by their fully qualified name."""
+    tokens = TOKEN.findall(path.read_text())
+    package = ""
+    scopes = []
+    comment = []
+    i = 0
+
+    def take():
+        nonlocal i
+        i += 1
+        return tokens[i - 1]
+
+    while i < len(tokens):
+        token = take()
+        if token.startswith("//"):
+            comment.append(token[2:].strip())
+            continue
+
+        doc = " ".join(line for line in comment if line)
+        comment = []
+        scope = scopes[-1] if scopes else None
+        if token in ("syntax", "import"):
+            while take() != ";":
+                pass
+        elif token == "package":
+            package = take()
+            take()
+        elif token == "message":
+            name = take()
+            take()
+            parent = scope[1] if scope else "." + package
+            message = Message(name, doc)
+            messages[f"{parent}.{name}"] = message
+            scopes.append(("message", f"{parent}.{name}", message))
+        elif token == "enum":
+            name = take()
+            take()
+            parent = scope[1] if scope else "." + package
+            values = []
+            while (value := take()) != "}":
+                if value.startswith("//"):
+                    continue
+                values.append(value)
+                while take() != ";":
+                    pass
+            enums[f"{parent}.{name}"] = (name, doc, values)
+        elif token == "oneof":
+            name = take()
+            take()
+            scope[2].oneofs.append((name, doc))
+            scopes.append(("oneof", scope[1], scope[2], name))
+        elif token == "}":
+            scopes.pop()
+        else:
+            label = None
+            if token in ("optional", "repeated"):
+                label, token = token, take()
+            name = take()
+            take()
+            take()
+            take()
+            oneof = scope[3] if scope[0] == "oneof" else None
+            scope[2].fields.append(
+                {
+                    "name": name,
+                    "type": token,
+                    "label": label,
+                    "oneof": oneof,
+                    "doc": doc,
+                    "scope": scope[1],
+                    "package": "." + package,
+                }
+            )
+
+
+def resolve(type_name, scope, package, messages, enums):
+    """Finds the fully qualified name of a type from the scope it is referred in."""
+    if type_name.startswith("."):
+        return type_name
+    candidates = [scope]
+    while "." in scope[1:]:
+        scope = scope.rsplit(".", 1)[0]
+        candidates.append(scope)
+    candidates.append("")
+    for candidate in candidates:
+        name = f"{candidate}.{type_name}"
+        if name in messages or name in enums:
+            return name
+    raise SystemExit(f"unknown type {type_name} in {scope}")
+
+
+def attributes(kind):
+    """The attributes that buf.gen.yaml attaches to the generated code, by proto path."""
+    attributes = {}
+    pattern = re.compile(rf"^\s*-\s*{kind}=(\.[\w.]+)=(.*)$")
+    for line in BUF_GEN.read_text().splitlines():
+        if match := pattern.match(line):
+            attributes.setdefault(match[1], []).append(match[2].replace("\\,", ","))
+    return attributes
+
+
+def with_module(attributes):
+    for attribute in attributes:
+        if match := re.search(r'serde\(with = "crate::json::(\w+)"\)', attribute):
+            return match[1]
+    return None
+
+
+def generate():
+    messages, enums = {}, {}
+    for path in PROTOS:
+        parse(path, messages, enums)
+    field_attributes = attributes("field_attribute")
+    type_attributes = attributes("type_attribute")
+
+    definitions = {
+        "uint64": {
+            "description": "An unsigned 64-bit integer, as a decimal string",
+            "type": "string",
+            "pattern": "^[0-9]+$",
+        },
+        "int64": {
+            "description": "A signed 64-bit integer, as a decimal string",
+            "type": "string",
+            "pattern": "^-?[0-9]+$",
+        },
+    }
+    pending = [ROOT_MESSAGE, *OTHER_MESSAGES]
+    defined = {}
+
+    def define(full_name):
+        name = full_name.rsplit(".", 1)[1]
+        if defined.setdefault(name, full_name) != full_name:
+            raise SystemExit(f"{full_name} and {defined[name]} have the same name")
+        if full_name in messages and full_name not in pending:
+            pending.append(full_name)
+        return {"$ref": f"#/definitions/{name}"}
+
+    def field_schema(message_name, field):
+        path = f"{message_name}.{field['name']}"
+        type_name = field["type"]
+        module = with_module(field_attributes.get(path, []))
+
+        if type_name in SCALARS:
+            if module:
+                raise SystemExit(f"{path} is a {type_name}, which needs no attribute")
+            schema = dict(SCALARS[type_name])
+        elif type_name in STRINGIFIED:
+            if module is None or module not in WITH_MODULES:
+                raise SystemExit(f"{path} is a {type_name} without its serde attribute")
+            if field["label"] == "optional" and not module.startswith("option_"):
+                raise SystemExit(f"{path} is optional, its attribute must handle None")
+            return dict(WITH_MODULES[module])
+        else:
+            full_name = resolve(type_name, message_name, field["package"], messages, enums)
+            if full_name in enums:
+                if module is None:
+                    raise SystemExit(f"{path} is an enum without its serde attribute")
+                schema = define(full_name)
+            else:
+                schema = define(full_name)
+                # prost wraps the message fields in an Option, but not the oneof variants
+                if field["label"] != "repeated" and field["oneof"] is None:
+                    schema = {"oneOf": [schema, {"type": "null"}]}
+
+        if field["label"] == "repeated":
+            return {"type": "array", "items": schema}
+        if field["label"] == "optional":
+            schema["type"] = [schema["type"], "null"]
+        return schema
+
+    while pending:
+        full_name = pending.pop(0)
+        message = messages[full_name]
+        properties = {}
+        # prost moves the oneofs after the other fields
+        for field in message.fields:
+            if field["oneof"] is None:
+                schema = field_schema(full_name, field)
+                if field["doc"]:
+                    schema = {"description": field["doc"], **schema}
+                properties[field["name"]] = schema
+        for oneof, doc in message.oneofs:
+            snake_case = any(
+                'rename_all = "snake_case"' in attribute
+                for attribute in type_attributes.get(f"{full_name}.{oneof}", [])
+            )
+            variants = [{"type": "null"}]
+            for field in message.fields:
+                if field["oneof"] == oneof:
+                    key = field["name"] if snake_case else camel_case(field["name"])
+                    variants.append(
+                        {
+                            "type": "object",
+                            "properties": {key: field_schema(full_name, field)},
+                            "required": [key],
+                            "additionalProperties": False,
+                        }
+                    )
+            schema = {"oneOf": variants}
+            if doc:
+                schema = {"description": doc, **schema}
+            properties[oneof] = schema
+
+        definition = {
+            "type": "object",
+            "properties": properties,
+            "required": list(properties),
+            "additionalProperties": False,
+        }
+        if message.comment:
+            definition = {"description": message.comment, **definition}
+        definitions[message.name] = definition
+
+    for full_name in defined.values():
+        if full_name in enums:
+            name, doc, values = enums[full_name]
+            definition = {
+                "oneOf": [
+                    {"enum": values},
+                    {
+                        "type": "integer",
+                        "description": "A value unknown to this version of the schema",
+                    },
+                ]
+            }
+            if doc:
+                definition = {"description": doc, **definition}
+            definitions[name] = definition
+
+    events = definitions.pop(ROOT_MESSAGE.rsplit(".", 1)[1])
+    return {
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "Events",
+        "description": "The output of map_block, sf.solana.spl.token.v1.Events, as serialized "
+        "with the 'serde' feature. 64-bit integers are decimal strings, enums are the names of "
+        "their values, or their number when unknown, and bytes are base58 encoded. Generated "
+        "by scripts/generate_schema.py.",
+        **{key: value for key, value in events.items() if key != "description"},
+        "definitions": definitions,
+    }
+
+
+def camel_case(name):
+    return "".join(part.capitalize() for part in name.split("_"))
+
+
+if __name__ == "__main__":
+    schema = json.dumps(generate(), indent=2) + "\n"
+    if "--check" in sys.argv[1:]:
+        if SCHEMA.read_text() != schema:
+            raise SystemExit(f"{SCHEMA.name} is out of date, run scripts/generate_schema.py")
+    else:
+        SCHEMA.write_text(schema)
@@ -0,0 +1,137 @@
+use crate::pb::sf::solana::spl::token::v1::{
+    event::Type,
+    set_authority::{AccountKind, AuthorityType},
+    Approve, CloseAccount, Events, FreezeAccount, InitializeAccount, InitializeMint, SetAuthority,
+    ThawAccount,
+};
+use substreams::store::{StoreNew, StoreSet, StoreSetString};
+
+/// Records the state of each token account, one key per column of the `token_accounts`
+/// table, keyed `<account>:<column>`. Each event only sets the columns it knows about, the
+/// other ones keep their last value, so that `db_out` can write complete rows.
+#[substreams::handlers::store]
+fn store_token_accounts(events: Events, store: StoreSetString) {
+    for (ordinal, event) in events.data.iter().enumerate() {
+        let ordinal = ordinal as u64;
+        let Some(event_type) = event.r#type.as_ref() else {
+            continue;
+        };
+        let set = |account: &str, column: &str, value: &str| {
+            store.set(ordinal, state_key(account, column), &value.to_string())
+        };
+
+        match event_type {
+            Type::InitializeAccount(InitializeAccount {
+                accounts,
+                is_associated_account,
+                ..
+            }) => {
+                let accounts = accounts.as_ref().unwrap();
+
+                set(&accounts.account, "owner", &accounts.owner);
+                set(&accounts.account, "mint", &accounts.mint);
+                set(
+                    &accounts.account,
+                    "is_associated_account",
+                    &is_associated_account.to_string(),
+                );
+                set(&accounts.account, "close_authority", "");
+                set(&accounts.account, "frozen", "false");
+                set(&accounts.account, "closed", "false");
+            }
+            Type::Approve(Approve { accounts, .. }) => {
+                let accounts = accounts.as_ref().unwrap();
+
+                // Only the owner of the account can approve a delegate
+                set(
+                    &accounts.source,
+                    "owner",
+                    accounts.signer.as_ref().unwrap().authority(),
+                );
+            }
+            Type::SetAuthority(SetAuthority {
+                instruction,
+                accounts,
+            }) => {
+                let instruction = instruction.as_ref().unwrap();
+                let account = &accounts.as_ref().unwrap().account;
+                let new_authority = instruction.new_authority.clone().unwrap_or_default();
+
+                match instruction.authority_type() {
+                    AuthorityType::AuthorityAccountOwner => set(account, "owner", &new_authority),
+                    AuthorityType::AuthorityCloseAccount => {
+                        set(account, "close_authority", &new_authority)
+                    }
+                    _ => {}
+                }
+            }
+            Type::FreezeAccount(FreezeAccount { accounts, .. }) => {
+                set(&accounts.as_ref().unwrap().account, "frozen", "true");
+            }
+            Type::ThawAccount(ThawAccount { accounts, .. }) => {
+                set(&accounts.as_ref().unwrap().account, "frozen", "false");
+            }
+            Type::CloseAccount(CloseAccount { accounts, .. }) => {
+                set(&accounts.as_ref().unwrap().account, "closed", "true");
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Records the decimals and authorities of each mint, keyed `<mint>:<column>` as
+/// [store_token_accounts] does, the supply being tracked by `store_mint_supply`.
+#[substreams::handlers::store]
+fn store_mints(events: Events, store: StoreSetString) {
+    for (ordinal, event) in events.data.iter().enumerate() {
+        let ordinal = ordinal as u64;
+        let Some(event_type) = event.r#type.as_ref() else {
+            continue;
+        };
+        let set = |mint: &str, column: &str, value: &str| {
+            store.set(ordinal, state_key(mint, column), &value.to_string())
+        };
+
+        match event_type {
+            Type::InitializeMint(InitializeMint {
+                instruction,
+                accounts,
+                ..
+            }) => {
+                let instruction = instruction.as_ref().unwrap();
+                let mint = &accounts.as_ref().unwrap().mint;
+
+                set(mint, "decimals", &instruction.decimals.to_string());
+                set(mint, "mint_authority", &instruction.mint_authority);
+                set(
+                    mint,
+                    "freeze_authority",
+                    &instruction.freeze_authority.clone().unwrap_or_default(),
+                );
+            }
+            Type::SetAuthority(SetAuthority {
+                instruction,
+                accounts,
+            }) if accounts.as_ref().unwrap().account_kind() == AccountKind::Mint => {
+                let instruction = instruction.as_ref().unwrap();
+                let mint = &accounts.as_ref().unwrap().account;
+                let new_authority = instruction.new_authority.clone().unwrap_or_default();
+
+                match instruction.authority_type() {
+                    AuthorityType::AuthorityMintTokens => {
+                        set(mint, "mint_authority", &new_authority)
+                    }
+                    AuthorityType::AuthorityFreezeAccount => {
+                        set(mint, "freeze_authority", &new_authority)
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+pub(crate) fn state_key(id: &str, column: &str) -> String {
+    format!("{id}:{column}")
+}
@@ -0,0 +1,72 @@
+use anyhow::anyhow;
+use spl_token::solana_program::{pubkey, pubkey::Pubkey};
+use substreams_solana::{block_view::InstructionView, Address};
+
+use crate::pb::sf::solana::spl::token::v1::{
+    associated_account_created::{
+        AssociatedAccountCreatedAccounts, AssociatedAccountCreatedInstruction,
+    },
+    associated_account_nested_recovered::{
+        AssociatedAccountNestedRecoveredAccounts, AssociatedAccountNestedRecoveredInstruction,
+    },
+    event::Type,
+    AssociatedAccountCreated, AssociatedAccountNestedRecovered,
+};
+
+pub(crate) const ASSOCIATED_TOKEN_ACCOUNT_PROGRAM_ID: Pubkey =
+    pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+/// Decodes an instruction of the Associated Token Account program. The original 'Create'
+/// instruction has no data at all, the other ones are identified by their first byte.
+impl TryFrom<&InstructionView<'_>> for Type {
+    type Error = substreams::errors::Error;
+
+    fn try_from(instruction_view: &InstructionView<'_>) -> Result<Self, Self::Error> {
+        let accounts = instruction_view.accounts();
+
+        Ok(match instruction_view.data().first() {
+            None | Some(0) | Some(1) => Type::AssociatedAccountCreated(AssociatedAccountCreated {
+                instruction: Some(AssociatedAccountCreatedInstruction {
+                    idempotent: instruction_view.data().first() == Some(&1),
+                }),
+                accounts: Some(AssociatedAccountCreatedAccounts {
+                    payer: account_at(&accounts, 0)?,
+                    associated_account: account_at(&accounts, 1)?,
+                    wallet: account_at(&accounts, 2)?,
+                    mint: account_at(&accounts, 3)?,
+                    token_program: account_at(&accounts, 5)?,
+                }),
+            }),
+            Some(2) => Type::AssociatedAccountNestedRecovered(AssociatedAccountNestedRecovered {
+                instruction: Some(AssociatedAccountNestedRecoveredInstruction {}),
+                accounts: Some(AssociatedAccountNestedRecoveredAccounts {
+                    nested_account: account_at(&accounts, 0)?,
+                    nested_mint: account_at(&accounts, 1)?,
+                    destination_account: account_at(&accounts, 2)?,
+                    owner_account: account_at(&accounts, 3)?,
+                    owner_mint: account_at(&accounts, 4)?,
+                    wallet: account_at(&accounts, 5)?,
+                    token_program: account_at(&accounts, 6)?,
+                }),
+            }),
+            Some(discriminator) => {
+                return Err(anyhow!(
+                    "Associated Token Account instruction {} is not supported",
+                    discriminator
+                ))
+            }
+        })
+    }
+}
+
+/// The account at index `at` of the instruction accounts, an instruction listing fewer
+/// accounts being malformed.
+pub(crate) fn account_at(
+    accounts: &[Address],
+    at: usize,
+) -> Result<String, substreams::errors::Error> {
+    accounts
+        .get(at)
+        .map(|account| account.to_string())
+        .ok_or_else(|| anyhow!("Missing account at index {}", at))
+}
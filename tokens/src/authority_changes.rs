@@ -0,0 +1,88 @@
+use crate::pb::sf::solana::spl::token::v1::{
+    event::Type,
+    set_authority::{AccountKind, AuthorityType},
+    AuthorityChange, AuthorityChanges, Event, Events, InitializeMint, SetAuthority,
+};
+use substreams::skip_empty_output;
+
+#[substreams::handlers::map]
+fn map_authority_changes(events: Events) -> Result<AuthorityChanges, substreams::errors::Error> {
+    skip_empty_output();
+
+    Ok(AuthorityChanges {
+        data: events.data.iter().flat_map(to_authority_changes).collect(),
+    })
+}
+
+/// Returns the authority changes of the event, one per authority type for the initialization
+/// of a mint, none if the event doesn't touch any authority.
+pub(crate) fn to_authority_changes(event: &Event) -> Vec<AuthorityChange> {
+    let Some(event_type) = event.r#type.as_ref() else {
+        return vec![];
+    };
+
+    match event_type {
+        Type::SetAuthority(SetAuthority {
+            instruction,
+            accounts,
+        }) => {
+            let instruction = instruction.as_ref().unwrap();
+            let accounts = accounts.as_ref().unwrap();
+
+            vec![AuthorityChange {
+                account: accounts.account.clone(),
+                account_kind: accounts.account_kind,
+                authority_type: instruction.authority_type,
+                old_authority: Some(accounts.current_authority.clone()),
+                new_authority: instruction.new_authority.clone(),
+                renounced: instruction.new_authority.is_none()
+                    && accounts.account_kind() == AccountKind::Mint,
+                ..event_authority_change(event)
+            }]
+        }
+        Type::InitializeMint(InitializeMint {
+            instruction,
+            accounts,
+            ..
+        }) => {
+            let instruction = instruction.as_ref().unwrap();
+            let mint = &accounts.as_ref().unwrap().mint;
+
+            [
+                (
+                    AuthorityType::AuthorityMintTokens,
+                    Some(instruction.mint_authority.clone()),
+                ),
+                (
+                    AuthorityType::AuthorityFreezeAccount,
+                    instruction.freeze_authority.clone(),
+                ),
+            ]
+            .into_iter()
+            .map(|(authority_type, new_authority)| AuthorityChange {
+                account: mint.clone(),
+                account_kind: AccountKind::Mint as i32,
+                authority_type: authority_type as i32,
+                old_authority: None,
+                new_authority,
+                ..event_authority_change(event)
+            })
+            .collect()
+        }
+        _ => vec![],
+    }
+}
+
+fn event_authority_change(event: &Event) -> AuthorityChange {
+    AuthorityChange {
+        txn_id: event.txn_id.clone(),
+        block_height: event.block_height,
+        block_timestamp: event.block_timestamp,
+        block_hash: event.block_hash.clone(),
+        instruction_index: event.instruction_index,
+        slot: event.slot,
+        parent_slot: event.parent_slot,
+        previous_block_hash: event.previous_block_hash.clone(),
+        ..Default::default()
+    }
+}
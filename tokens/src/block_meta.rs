@@ -0,0 +1,69 @@
+use crate::pb::sf::solana::spl::token::v1::BlockMeta;
+use substreams_solana::pb::sf::solana::r#type::v1::Block;
+
+/// Extracts the header of the block, whose slot, block height and parent slot the `Clock`
+/// doesn't tell apart, for the modules labeling their output with the block.
+#[substreams::handlers::map]
+fn map_block_meta(block: Block) -> Result<BlockMeta, substreams::errors::Error> {
+    Ok(to_block_meta(&block))
+}
+
+pub(crate) fn to_block_meta(block: &Block) -> BlockMeta {
+    BlockMeta {
+        slot: block.slot,
+        block_height: block
+            .block_height
+            .as_ref()
+            .map(|height| height.block_height)
+            .unwrap_or_default(),
+        parent_slot: block.parent_slot,
+        block_hash: block.blockhash.clone(),
+        previous_block_hash: block.previous_blockhash.clone(),
+        block_timestamp: block
+            .block_time
+            .as_ref()
+            .map(|time| time.timestamp)
+            .unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use substreams_solana::pb::sf::solana::r#type::v1::{BlockHeight, UnixTimestamp};
+
+    #[test]
+    fn test_to_block_meta() {
+        let block = Block {
+            slot: 250_000_010,
+            parent_slot: 250_000_007,
+            block_height: Some(BlockHeight {
+                block_height: 228_000_000,
+            }),
+            blockhash: "block".to_string(),
+            previous_blockhash: "previous".to_string(),
+            block_time: Some(UnixTimestamp {
+                timestamp: 1_700_000_000,
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            to_block_meta(&block),
+            BlockMeta {
+                slot: 250_000_010,
+                block_height: 228_000_000,
+                parent_slot: 250_000_007,
+                block_hash: "block".to_string(),
+                previous_block_hash: "previous".to_string(),
+                block_timestamp: 1_700_000_000,
+            }
+        );
+
+        let block = Block {
+            slot: 1,
+            ..Default::default()
+        };
+        assert_eq!(to_block_meta(&block).block_height, 0);
+    }
+}
@@ -1,67 +1,499 @@
-use crate::pb::sf::solana::spl::token::v1::{event::Type, signer, Events, Signer, Transfer};
-use substreams::skip_empty_output;
+use std::collections::BTreeSet;
+
+use crate::account_state::state_key;
+use crate::authority_changes::to_authority_changes;
+use crate::delegations::{delegate_key, delegated_amount_key};
+use crate::pb::sf::solana::spl::token::v1::{
+    event::Type, set_authority::AccountKind, Approve, AssociatedAccountCreated, AuthorityChange,
+    BalanceChange, BlockMeta, Burn, CloseAccount, Event, Events, FreezeAccount, InitializeAccount,
+    InitializeMint, MintTo, Revoke, SetAuthority, Signer, ThawAccount, Transaction, Transfer,
+};
+use prost_types::Timestamp;
+use substreams::{
+    pb::substreams::{store_delta::Operation, Clock},
+    scalar::BigInt,
+    skip_empty_output,
+    store::{DeltaBigInt, Deltas, StoreGet, StoreGetBigInt, StoreGetString},
+};
 use substreams_database_change::{
     pb::database::DatabaseChanges,
     tables::{Row, Tables},
 };
 
+#[allow(clippy::too_many_arguments)]
 #[substreams::handlers::map]
-fn db_out(events: Events) -> Result<DatabaseChanges, substreams::errors::Error> {
+fn db_out(
+    clock: Clock,
+    block: BlockMeta,
+    events: Events,
+    token_accounts: StoreGetString,
+    mints: StoreGetString,
+    delegates: StoreGetString,
+    delegated_amounts: StoreGetBigInt,
+    mint_supply: StoreGetBigInt,
+    mint_stats_deltas: Deltas<DeltaBigInt>,
+) -> Result<DatabaseChanges, substreams::errors::Error> {
     skip_empty_output();
 
+    let state = Stores {
+        token_accounts,
+        mints,
+        delegates,
+        delegated_amounts,
+        mint_supply,
+    };
+
+    Ok(to_database_changes(
+        &clock,
+        &block,
+        events,
+        &state,
+        mint_stats_deltas,
+    ))
+}
+
+/// The state of the token accounts and mints at the end of the block. The `token_accounts`
+/// and `mints` tables replace whole rows, so their rows are always written complete from this
+/// state rather than from the columns a single event knows about.
+trait State {
+    fn token_account(&self, account: &str, column: &str) -> Option<String>;
+    fn mint(&self, mint: &str, column: &str) -> Option<String>;
+    fn delegate(&self, account: &str) -> Option<String>;
+    fn delegated_amount(&self, account: &str) -> Option<BigInt>;
+    fn supply(&self, mint: &str) -> Option<BigInt>;
+}
+
+struct Stores {
+    token_accounts: StoreGetString,
+    mints: StoreGetString,
+    delegates: StoreGetString,
+    delegated_amounts: StoreGetBigInt,
+    mint_supply: StoreGetBigInt,
+}
+
+impl State for Stores {
+    fn token_account(&self, account: &str, column: &str) -> Option<String> {
+        self.token_accounts.get_last(state_key(account, column))
+    }
+
+    fn mint(&self, mint: &str, column: &str) -> Option<String> {
+        self.mints.get_last(state_key(mint, column))
+    }
+
+    fn delegate(&self, account: &str) -> Option<String> {
+        self.delegates.get_last(delegate_key(account))
+    }
+
+    fn delegated_amount(&self, account: &str) -> Option<BigInt> {
+        self.delegated_amounts
+            .get_last(delegated_amount_key(account))
+    }
+
+    fn supply(&self, mint: &str) -> Option<BigInt> {
+        self.mint_supply.get_last(mint)
+    }
+}
+
+fn to_database_changes(
+    clock: &Clock,
+    block: &BlockMeta,
+    events: Events,
+    state: &impl State,
+    mint_stats_deltas: Deltas<DeltaBigInt>,
+) -> DatabaseChanges {
     let mut tables = Tables::new();
+    let mut token_accounts: BTreeSet<String> = BTreeSet::new();
+    let mut mints: BTreeSet<String> = BTreeSet::new();
 
     for event in events.data {
-        let event_type = event.r#type.as_ref().unwrap();
+        // Instructions that aren't modeled only come with their raw instruction
+        let Some(event_type) = event.r#type.as_ref() else {
+            continue;
+        };
+        let (token_account, mint) = changed_state(event_type, state);
+        token_accounts.extend(token_account.map(str::to_string));
+        mints.extend(mint.map(str::to_string));
+        add_frozen_account(&mut tables, clock, event_type);
 
-        let row = tables
-            .create_row(
-                event_type.to_table(),
-                [
-                    ("evt_tx", event.txn_id.clone()),
-                    ("evt_instruction_index", event.instruction_index.to_string()),
-                ],
-            )
-            .set("evt_block_timestamp", event.block_timestamp)
-            .set("evt_block_height", event.block_height)
-            .set("evt_block_hash", &event.block_hash);
+        for change in to_authority_changes(&event) {
+            add_authority_change(&mut tables, &change);
+        }
 
         match event_type {
             Type::Transfer(Transfer {
                 instruction,
                 accounts,
+                memo,
             }) => {
                 let instruction = instruction.as_ref().unwrap();
                 let accounts = accounts.as_ref().unwrap();
 
-                row.set("amount", instruction.amount)
+                create_event_row(&mut tables, &event)
+                    .set("amount", instruction.amount)
                     .set("source", &accounts.source)
                     .set("destination", &accounts.destination)
-                    .set_signer("signers", accounts.signer.as_ref().unwrap());
+                    .set("memo", memo.clone().unwrap_or_default())
+                    .set_signer(accounts.signer.as_ref().unwrap());
+            }
+            Type::AssociatedAccountCreated(AssociatedAccountCreated {
+                instruction,
+                accounts,
+            }) => {
+                let accounts = accounts.as_ref().unwrap();
+
+                create_event_row(&mut tables, &event)
+                    .set("idempotent", instruction.as_ref().unwrap().idempotent)
+                    .set("payer", &accounts.payer)
+                    .set("associated_account", &accounts.associated_account)
+                    .set("wallet", &accounts.wallet)
+                    .set("mint", &accounts.mint)
+                    .set("token_program", &accounts.token_program);
+            }
+            Type::CloseAccount(CloseAccount {
+                accounts,
+                mint,
+                amount,
+                reclaimed_lamports,
+                ..
+            }) => {
+                let accounts = accounts.as_ref().unwrap();
+
+                create_event_row(&mut tables, &event)
+                    .set("account", &accounts.account)
+                    .set("destination", &accounts.destination)
+                    .set("mint", mint)
+                    .set("amount", *amount)
+                    .set("reclaimed_lamports", *reclaimed_lamports)
+                    .set_signer(accounts.signer.as_ref().unwrap());
             }
             _ => continue,
         }
+
+        if let Some(signer) = event_type.signer() {
+            add_instruction_signers(&mut tables, &event, signer);
+        }
+    }
+
+    for transaction in events.transactions {
+        add_transaction(&mut tables, block, &transaction);
+    }
+
+    for change in events.balance_changes {
+        add_balance(&mut tables, clock, &change);
+    }
+
+    for account in &token_accounts {
+        add_token_account(&mut tables, clock, state, account);
+    }
+
+    for mint in &mints {
+        add_mint(&mut tables, clock, state, mint);
+    }
+
+    for delta in mint_stats_deltas.deltas {
+        add_mint_stats(&mut tables, clock, &delta);
     }
 
-    Ok(tables.to_database_changes())
+    tables.to_database_changes()
 }
 
-trait SetSigners {
-    fn set_signer(&mut self, name: &str, signer: &Signer) -> &mut Row;
+/// Creates the row of the event in its own table, keyed by transaction and instruction
+/// index, with the common `evt_*` columns set.
+fn create_event_row<'a>(tables: &'a mut Tables, event: &Event) -> &'a mut Row {
+    tables
+        .create_row(
+            event.r#type.as_ref().unwrap().to_table(),
+            [
+                ("evt_tx", event.txn_id.clone()),
+                ("evt_instruction_index", event.instruction_index.to_string()),
+            ],
+        )
+        .set("evt_block_timestamp", event.block_timestamp)
+        .set("evt_slot", event.slot)
+        .set("evt_block_height", event.block_height)
+        .set("evt_block_hash", &event.block_hash)
+        .set("evt_parent_slot", event.parent_slot)
+        .set("evt_previous_block_hash", &event.previous_block_hash)
+        .set("evt_outer_instruction_index", event.outer_instruction_index)
+        .set(
+            "evt_inner_instruction_index",
+            event.inner_instruction_index.unwrap_or_default(),
+        )
+        .set("evt_stack_height", event.stack_height)
+        .set(
+            "evt_parent_program_id",
+            event.parent_program_id.clone().unwrap_or_default(),
+        )
+        .set(
+            "evt_instruction_kind",
+            event.instruction_kind().as_str_name(),
+        )
 }
 
-impl SetSigners for Row {
-    fn set_signer(&mut self, name: &str, signer: &Signer) -> &mut Row {
-        match signer.kind.as_ref().unwrap() {
-            signer::Kind::Single(single) => {
-                self.set(name, &single.signer);
-            }
-            signer::Kind::Multisig(multi) => {
-                self.set(name, multi.signers.join(","));
+fn add_authority_change(tables: &mut Tables, change: &AuthorityChange) {
+    let row = tables
+        .create_row(
+            "authority_changes",
+            [
+                ("evt_tx", change.txn_id.clone()),
+                (
+                    "evt_instruction_index",
+                    change.instruction_index.to_string(),
+                ),
+                (
+                    "authority_type",
+                    change.authority_type().as_str_name().to_string(),
+                ),
+            ],
+        )
+        .set("account", &change.account)
+        .set("account_kind", change.account_kind().as_str_name())
+        .set("renounced", change.renounced)
+        .set("evt_block_timestamp", change.block_timestamp)
+        .set("evt_slot", change.slot)
+        .set("evt_block_height", change.block_height)
+        .set("evt_block_hash", &change.block_hash)
+        .set("evt_parent_slot", change.parent_slot)
+        .set("evt_previous_block_hash", &change.previous_block_hash);
+
+    if let Some(old_authority) = &change.old_authority {
+        row.set("old_authority", old_authority);
+    }
+    if let Some(new_authority) = &change.new_authority {
+        row.set("new_authority", new_authority);
+    }
+}
+
+fn add_transaction(tables: &mut Tables, block: &BlockMeta, transaction: &Transaction) {
+    let row = tables
+        .create_row("transactions", &transaction.txn_id)
+        .set("index", transaction.index)
+        .set("fee_payer", &transaction.fee_payer)
+        .set_clickhouse_array("signers", transaction.signers.clone())
+        .set("fee", transaction.fee)
+        .set("recent_blockhash", &transaction.recent_blockhash)
+        .set("success", transaction.success)
+        .set("evt_block_timestamp", block.block_timestamp)
+        .set("evt_slot", block.slot)
+        .set("evt_block_height", block.block_height)
+        .set("evt_block_hash", &block.block_hash)
+        .set("evt_parent_slot", block.parent_slot)
+        .set("evt_previous_block_hash", &block.previous_block_hash);
+
+    if let Some(compute_units_consumed) = transaction.compute_units_consumed {
+        row.set("compute_units_consumed", compute_units_consumed);
+    }
+}
+
+/// Returns the token account and the mint whose row the event changes, if any. Transfers and
+/// burns change the source account when it has a delegate, whose allowance they may consume.
+fn changed_state<'a>(
+    event_type: &'a Type,
+    state: &impl State,
+) -> (Option<&'a str>, Option<&'a str>) {
+    match event_type {
+        Type::InitializeAccount(InitializeAccount { accounts, .. }) => {
+            (Some(&accounts.as_ref().unwrap().account), None)
+        }
+        Type::InitializeMint(InitializeMint { accounts, .. }) => {
+            (None, Some(&accounts.as_ref().unwrap().mint))
+        }
+        Type::Approve(Approve { accounts, .. }) => (Some(&accounts.as_ref().unwrap().source), None),
+        Type::Revoke(Revoke { accounts, .. }) => (Some(&accounts.as_ref().unwrap().source), None),
+        Type::Transfer(Transfer { accounts, .. }) => {
+            let source = &accounts.as_ref().unwrap().source;
+            (state.delegate(source).map(|_| source.as_str()), None)
+        }
+        Type::Burn(Burn { accounts, .. }) => {
+            let accounts = accounts.as_ref().unwrap();
+            (
+                state
+                    .delegate(&accounts.source)
+                    .map(|_| accounts.source.as_str()),
+                Some(&accounts.mint),
+            )
+        }
+        Type::MintTo(MintTo { accounts, .. }) => (None, Some(&accounts.as_ref().unwrap().mint)),
+        Type::SetAuthority(SetAuthority { accounts, .. }) => {
+            let accounts = accounts.as_ref().unwrap();
+            match accounts.account_kind() {
+                AccountKind::Mint => (None, Some(&accounts.account)),
+                _ => (Some(&accounts.account), None),
             }
         }
+        Type::FreezeAccount(FreezeAccount { accounts, .. }) => {
+            (Some(&accounts.as_ref().unwrap().account), None)
+        }
+        Type::ThawAccount(ThawAccount { accounts, .. }) => {
+            (Some(&accounts.as_ref().unwrap().account), None)
+        }
+        Type::CloseAccount(CloseAccount { accounts, .. }) => {
+            (Some(&accounts.as_ref().unwrap().account), None)
+        }
+        _ => (None, None),
+    }
+}
+
+/// Writes the complete `token_accounts` row of the account from its state at the end of the
+/// block. The delegate is cleared once its whole allowance is consumed, as the token program
+/// does.
+fn add_token_account(tables: &mut Tables, clock: &Clock, state: &impl State, account: &String) {
+    let column = |name: &str| state.token_account(account, name).unwrap_or_default();
+    let delegated_amount = state.delegated_amount(account).unwrap_or_else(BigInt::zero);
+    let delegate = if delegated_amount.is_zero() {
+        String::new()
+    } else {
+        state.delegate(account).unwrap_or_default()
+    };
+
+    create_state_row(tables, clock, "token_accounts", account)
+        .set("owner", column("owner"))
+        .set("mint", column("mint"))
+        .set("delegate", delegate)
+        .set("delegated_amount", delegated_amount)
+        .set("close_authority", column("close_authority"))
+        .set(
+            "is_associated_account",
+            column("is_associated_account") == "true",
+        )
+        .set("frozen", column("frozen") == "true")
+        .set("closed", column("closed") == "true");
+}
+
+/// Writes the complete `mints` row of the mint from its state at the end of the block.
+fn add_mint(tables: &mut Tables, clock: &Clock, state: &impl State, mint: &String) {
+    let column = |name: &str| state.mint(mint, name).unwrap_or_default();
 
-        self
+    create_state_row(tables, clock, "mints", mint)
+        .set("supply", state.supply(mint).unwrap_or_else(BigInt::zero))
+        .set(
+            "decimals",
+            column("decimals").parse::<u32>().unwrap_or_default(),
+        )
+        .set("mint_authority", column("mint_authority"))
+        .set("freeze_authority", column("freeze_authority"));
+}
+
+/// Writes the `frozen_accounts` row of the account the event freezes or thaws, if any.
+fn add_frozen_account(tables: &mut Tables, clock: &Clock, event_type: &Type) {
+    let (account, mint, freeze_authority, frozen) = match event_type {
+        Type::FreezeAccount(FreezeAccount { accounts, .. }) => {
+            let accounts = accounts.as_ref().unwrap();
+            (
+                &accounts.account,
+                &accounts.mint,
+                accounts.mint_freeze_authority.as_ref().unwrap(),
+                true,
+            )
+        }
+        Type::ThawAccount(ThawAccount { accounts, .. }) => {
+            let accounts = accounts.as_ref().unwrap();
+            (
+                &accounts.account,
+                &accounts.mint,
+                accounts.mint_freeze_authority.as_ref().unwrap(),
+                false,
+            )
+        }
+        _ => return,
+    };
+
+    create_state_row(tables, clock, "frozen_accounts", account)
+        .set("mint", mint)
+        .set("frozen", frozen)
+        .set("freeze_authority", freeze_authority.authority());
+}
+
+fn add_balance(tables: &mut Tables, clock: &Clock, change: &BalanceChange) {
+    create_state_row(tables, clock, "balances", &change.account)
+        .set("mint", &change.mint)
+        .set("owner", &change.owner)
+        .set("amount", change.post_amount)
+        .set("decimals", change.decimals)
+        .set("evt_tx", &change.txn_id);
+}
+
+/// Inserts the metric of the `mint_stats_hourly` or `mint_stats_daily` row the delta is for,
+/// which the table merges with the other metrics of the row. Deletions of completed buckets
+/// from the store are ignored.
+fn add_mint_stats(tables: &mut Tables, clock: &Clock, delta: &DeltaBigInt) {
+    if delta.operation == Operation::Delete {
+        return;
+    }
+
+    // <period>:<bucket>:<mint>:<metric>
+    let segments: Vec<&str> = delta.key.split(':').collect();
+    tables
+        .create_row(
+            &format!("mint_stats_{}", segments[0]),
+            [
+                ("mint", segments[2].to_string()),
+                ("period_start", period_start(segments[1])),
+            ],
+        )
+        .set(segments[3], delta.new_value.clone())
+        .set("version", clock.number);
+}
+
+/// Formats the start timestamp of a bucket as a `Timestamp` value, i.e. RFC 3339, which the
+/// sink writes into the `DateTime` column.
+fn period_start(bucket: &str) -> String {
+    Timestamp {
+        seconds: bucket.parse().unwrap_or_default(),
+        nanos: 0,
+    }
+    .to_string()
+}
+
+/// Inserts a new version of the row, which replaces the previous ones of the same key as the
+/// state tables are `ReplacingMergeTree` versioned by `version`.
+fn create_state_row<'a>(
+    tables: &'a mut Tables,
+    clock: &Clock,
+    table: &str,
+    key: &String,
+) -> &'a mut Row {
+    tables.create_row(table, key).set("version", clock.number)
+}
+
+/// Emits one `instruction_signers` row per key that signed the instruction, so that
+/// instructions can be looked up by any of their co-signers.
+fn add_instruction_signers(tables: &mut Tables, event: &Event, signer: &Signer) {
+    let event_type = event.r#type.as_ref().unwrap();
+
+    for key in signer.signing_keys() {
+        tables
+            .create_row(
+                "instruction_signers",
+                [
+                    ("evt_tx", event.txn_id.clone()),
+                    ("evt_instruction_index", event.instruction_index.to_string()),
+                    ("signer", key),
+                ],
+            )
+            .set("evt_block_timestamp", event.block_timestamp)
+            .set("evt_slot", event.slot)
+            .set("evt_block_height", event.block_height)
+            .set("evt_block_hash", &event.block_hash)
+            .set("evt_parent_slot", event.parent_slot)
+            .set("evt_previous_block_hash", &event.previous_block_hash)
+            .set("evt_table", event_type.to_table())
+            .set("multisig_account", signer.multisig_account());
+    }
+}
+
+trait SetSigners {
+    fn set_signer(&mut self, signer: &Signer) -> &mut Row;
+}
+
+impl SetSigners for Row {
+    /// Sets the `signer`, `multisig_account` and `signers` columns. The `signer` column is
+    /// the authority of the instruction, i.e. the multisig account itself when the authority
+    /// is a multisig, while `signers` always holds the keys that actually signed.
+    fn set_signer(&mut self, signer: &Signer) -> &mut Row {
+        self.set("signer", signer.authority())
+            .set("multisig_account", signer.multisig_account())
+            .set_clickhouse_array("signers", signer.signing_keys())
     }
 }
 
@@ -82,6 +514,396 @@ impl Type {
             Type::FreezeAccount(_) => "freeze_account",
             Type::ThawAccount(_) => "thaw_account",
             Type::SyncNative(_) => "sync_native",
+            Type::AssociatedAccountCreated(_) => "associated_account_created",
+            Type::AssociatedAccountNestedRecovered(_) => "associated_account_nested_recovered",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    use substreams::{
+        pb::substreams::{store_delta::Operation, Clock},
+        scalar::BigInt,
+        store::{DeltaBigInt, Deltas},
+    };
+    use substreams_database_change::pb::database::{table_change::PrimaryKey, TableChange};
+
+    use super::State;
+
+    use crate::pb::sf::solana::spl::token::v1::{
+        approve::{ApproveAccounts, ApproveInstruction},
+        associated_account_created::{
+            AssociatedAccountCreatedAccounts, AssociatedAccountCreatedInstruction,
+        },
+        close_account::{CloseAccountAccounts, CloseAccountInstruction},
+        event::Type,
+        freeze_account::{FreezeAccountAccounts, FreezeAccountInstruction},
+        initialize_account::{InitializeAccountAccounts, InitializeAccountInstruction},
+        set_authority::{
+            AccountKind, AuthorityType, SetAuthorityAccounts, SetAuthorityInstruction,
+        },
+        signer::Kind,
+        transfer::{TransferAccounts, TransferInstruction},
+        Approve, AssociatedAccountCreated, BalanceChange, BlockMeta, CloseAccount, Event, Events,
+        FreezeAccount, InitializeAccount, MultiSignature, SetAuthority, Signer, SingleSignature,
+        Transaction, Transfer,
+    };
+
+    /// The state of the stores read by `db_out`, keyed as the stores are.
+    #[derive(Default)]
+    struct TestState {
+        token_accounts: HashMap<String, String>,
+        mints: HashMap<String, String>,
+        delegates: HashMap<String, String>,
+        delegated_amounts: HashMap<String, u64>,
+        supplies: HashMap<String, u64>,
+    }
+
+    impl State for TestState {
+        fn token_account(&self, account: &str, column: &str) -> Option<String> {
+            self.token_accounts
+                .get(&format!("{account}:{column}"))
+                .cloned()
+        }
+
+        fn mint(&self, mint: &str, column: &str) -> Option<String> {
+            self.mints.get(&format!("{mint}:{column}")).cloned()
+        }
+
+        fn delegate(&self, account: &str) -> Option<String> {
+            self.delegates.get(account).cloned()
+        }
+
+        fn delegated_amount(&self, account: &str) -> Option<BigInt> {
+            self.delegated_amounts
+                .get(account)
+                .copied()
+                .map(BigInt::from)
+        }
+
+        fn supply(&self, mint: &str) -> Option<BigInt> {
+            self.supplies.get(mint).copied().map(BigInt::from)
+        }
+    }
+
+    fn clock() -> Clock {
+        Clock {
+            id: "hash".to_string(),
+            number: 10,
+            timestamp: None,
+        }
+    }
+
+    fn block() -> BlockMeta {
+        BlockMeta {
+            slot: 10,
+            block_height: 8,
+            parent_slot: 9,
+            block_hash: "hash".to_string(),
+            previous_block_hash: "previous".to_string(),
+            block_timestamp: 1_700_000_000,
+        }
+    }
+
+    fn no_stats() -> Deltas<DeltaBigInt> {
+        Deltas { deltas: vec![] }
+    }
+
+    /// Returns the columns set by the row of `id` in `table`.
+    fn state_row(changes: &[TableChange], table: &str, id: &str) -> HashMap<String, String> {
+        let change = changes
+            .iter()
+            .find(|change| {
+                change.table == table && change.primary_key == Some(PrimaryKey::Pk(id.to_string()))
+            })
+            .unwrap_or_else(|| panic!("no {table} row for {id}"));
+
+        change
+            .fields
+            .iter()
+            .map(|field| (field.name.clone(), field.new_value.clone()))
+            .collect()
+    }
+
+    /// Parses `schema.sql` into the set of columns of each table.
+    fn schema_columns() -> HashMap<String, HashSet<String>> {
+        let mut tables: HashMap<String, HashSet<String>> = HashMap::new();
+        let mut current = None;
+
+        for line in include_str!("../schema.sql").lines().map(str::trim) {
+            if let Some(name) = line.strip_prefix("CREATE TABLE IF NOT EXISTS ") {
+                let name = name.trim_end_matches(" (").to_string();
+                tables.insert(name.clone(), HashSet::new());
+                current = Some(name);
+            } else if line.starts_with(')') {
+                current = None;
+            } else if let (Some(table), Some(rest)) = (&current, line.strip_prefix('"')) {
+                let column = &rest[..rest.find('"').unwrap()];
+                tables.get_mut(table).unwrap().insert(column.to_string());
+            }
+        }
+
+        tables
+    }
+
+    fn event(instruction_index: u32, r#type: Type) -> Event {
+        Event {
+            txn_id: "tx".to_string(),
+            slot: 10,
+            block_height: 8,
+            block_timestamp: 1_700_000_000,
+            block_hash: "hash".to_string(),
+            parent_slot: 9,
+            previous_block_hash: "previous".to_string(),
+            instruction_index,
+            r#type: Some(r#type),
+            ..Default::default()
         }
     }
+
+    #[test]
+    fn test_db_out_matches_schema() {
+        let events = Events {
+            data: vec![
+                event(
+                    0,
+                    Type::InitializeAccount(InitializeAccount {
+                        version: 3,
+                        instruction: Some(InitializeAccountInstruction {}),
+                        accounts: Some(InitializeAccountAccounts {
+                            account: "account".to_string(),
+                            mint: "mint".to_string(),
+                            owner: "owner".to_string(),
+                        }),
+                        is_associated_account: true,
+                    }),
+                ),
+                event(
+                    1,
+                    Type::Transfer(Transfer {
+                        instruction: Some(TransferInstruction {
+                            amount: 100,
+                            decimals: None,
+                        }),
+                        accounts: Some(TransferAccounts {
+                            source: "account".to_string(),
+                            destination: "destination".to_string(),
+                            token_mint: None,
+                            signer: Some(Signer {
+                                kind: Some(Kind::Multisig(MultiSignature {
+                                    multisig_account: "multisig".to_string(),
+                                    signers: vec!["a".to_string(), "b".to_string()],
+                                    ..Default::default()
+                                })),
+                            }),
+                        }),
+                        memo: Some("deposit".to_string()),
+                    }),
+                ),
+                event(
+                    2,
+                    Type::AssociatedAccountCreated(AssociatedAccountCreated {
+                        instruction: Some(AssociatedAccountCreatedInstruction { idempotent: true }),
+                        accounts: Some(AssociatedAccountCreatedAccounts {
+                            payer: "owner".to_string(),
+                            associated_account: "account".to_string(),
+                            wallet: "owner".to_string(),
+                            mint: "mint".to_string(),
+                            token_program: "program".to_string(),
+                        }),
+                    }),
+                ),
+                event(
+                    3,
+                    Type::CloseAccount(CloseAccount {
+                        instruction: Some(CloseAccountInstruction {}),
+                        accounts: Some(CloseAccountAccounts {
+                            account: "account".to_string(),
+                            destination: "owner".to_string(),
+                            signer: Some(Signer {
+                                kind: Some(Kind::Single(SingleSignature {
+                                    signer: "owner".to_string(),
+                                })),
+                            }),
+                        }),
+                        mint: "mint".to_string(),
+                        amount: 0,
+                        reclaimed_lamports: 2039280,
+                    }),
+                ),
+                event(
+                    4,
+                    Type::FreezeAccount(FreezeAccount {
+                        instruction: Some(FreezeAccountInstruction {}),
+                        accounts: Some(FreezeAccountAccounts {
+                            account: "account".to_string(),
+                            mint: "mint".to_string(),
+                            mint_freeze_authority: Some(Signer {
+                                kind: Some(Kind::Single(SingleSignature {
+                                    signer: "authority".to_string(),
+                                })),
+                            }),
+                        }),
+                    }),
+                ),
+                event(
+                    5,
+                    Type::SetAuthority(SetAuthority {
+                        instruction: Some(SetAuthorityInstruction {
+                            authority_type: AuthorityType::AuthorityMintTokens as i32,
+                            new_authority: None,
+                        }),
+                        accounts: Some(SetAuthorityAccounts {
+                            account: "mint".to_string(),
+                            current_authority: "owner".to_string(),
+                            account_kind: AccountKind::Mint as i32,
+                            signer: None,
+                        }),
+                    }),
+                ),
+            ],
+            balance_changes: vec![BalanceChange {
+                txn_id: "tx".to_string(),
+                account: "account".to_string(),
+                mint: "mint".to_string(),
+                owner: "owner".to_string(),
+                pre_amount: 100,
+                post_amount: 0,
+                decimals: 6,
+            }],
+            transactions: vec![Transaction {
+                txn_id: "tx".to_string(),
+                index: 0,
+                fee_payer: "a".to_string(),
+                signers: vec!["a".to_string()],
+                fee: 5000,
+                compute_units_consumed: Some(1000),
+                recent_blockhash: "blockhash".to_string(),
+                success: true,
+            }],
+        };
+        let state = TestState {
+            delegates: HashMap::from([("account".to_string(), "delegate".to_string())]),
+            delegated_amounts: HashMap::from([("account".to_string(), 100)]),
+            supplies: HashMap::from([("mint".to_string(), 200)]),
+            ..Default::default()
+        };
+
+        let stats_deltas = Deltas {
+            deltas: vec![DeltaBigInt {
+                operation: Operation::Create,
+                ordinal: 0,
+                key: "hourly:1699999200:mint:transfer_count".to_string(),
+                old_value: BigInt::from(0u64),
+                new_value: BigInt::from(1u64),
+            }],
+        };
+
+        let schema = schema_columns();
+        let changes = super::to_database_changes(&clock(), &block(), events, &state, stats_deltas);
+        assert!(!changes.table_changes.is_empty());
+
+        for change in changes.table_changes {
+            let columns = schema
+                .get(&change.table)
+                .unwrap_or_else(|| panic!("table {} is missing from schema.sql", change.table));
+
+            for field in &change.fields {
+                assert!(
+                    columns.contains(&field.name),
+                    "column {}.{} is missing from schema.sql",
+                    change.table,
+                    field.name
+                );
+            }
+
+            match change.primary_key.unwrap() {
+                PrimaryKey::Pk(_) => {
+                    assert!(columns.contains("id"));
+
+                    // The state tables replace whole rows, a partial row would lose columns
+                    let fields: HashSet<&String> =
+                        change.fields.iter().map(|field| &field.name).collect();
+                    for column in columns.iter().filter(|column| *column != "id") {
+                        assert!(
+                            fields.contains(column),
+                            "column {}.{} is not written",
+                            change.table,
+                            column
+                        );
+                    }
+                }
+                PrimaryKey::CompositePk(pk) => {
+                    for key in pk.keys.keys() {
+                        assert!(columns.contains(key), "key {}.{}", change.table, key);
+                    }
+                    if change.table == "mint_stats_hourly" {
+                        assert_eq!(pk.keys["period_start"], "2023-11-14T22:00:00Z");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_db_out_writes_state_rows_from_stores() {
+        // The account and the mint were initialized in earlier blocks, this block only
+        // approves a delegate and transfers tokens
+        let state = TestState {
+            token_accounts: HashMap::from([
+                ("account:owner".to_string(), "owner".to_string()),
+                ("account:mint".to_string(), "mint".to_string()),
+                (
+                    "account:is_associated_account".to_string(),
+                    "true".to_string(),
+                ),
+                ("account:frozen".to_string(), "false".to_string()),
+                ("account:closed".to_string(), "false".to_string()),
+            ]),
+            mints: HashMap::from([
+                ("mint:decimals".to_string(), "6".to_string()),
+                ("mint:mint_authority".to_string(), "authority".to_string()),
+            ]),
+            delegates: HashMap::from([("account".to_string(), "delegate".to_string())]),
+            delegated_amounts: HashMap::from([("account".to_string(), 40)]),
+            supplies: HashMap::from([("mint".to_string(), 1000)]),
+        };
+        let owner = Signer {
+            kind: Some(Kind::Single(SingleSignature {
+                signer: "owner".to_string(),
+            })),
+        };
+        let events = Events {
+            data: vec![event(
+                0,
+                Type::Approve(Approve {
+                    instruction: Some(ApproveInstruction {
+                        amount: 100,
+                        decimals: None,
+                    }),
+                    accounts: Some(ApproveAccounts {
+                        source: "account".to_string(),
+                        token_mint: None,
+                        delegate: "delegate".to_string(),
+                        signer: Some(owner),
+                    }),
+                }),
+            )],
+            ..Default::default()
+        };
+
+        let changes = super::to_database_changes(&clock(), &block(), events, &state, no_stats())
+            .table_changes;
+
+        let account = state_row(&changes, "token_accounts", "account");
+        assert_eq!(account["owner"], "owner");
+        assert_eq!(account["mint"], "mint");
+        assert_eq!(account["delegate"], "delegate");
+        assert_eq!(account["delegated_amount"], "40");
+        assert_eq!(account["is_associated_account"], "true");
+        assert_eq!(account["frozen"], "false");
+    }
 }
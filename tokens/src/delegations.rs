@@ -0,0 +1,123 @@
+use crate::pb::sf::solana::spl::token::v1::{
+    event::Type, set_authority::AuthorityType, Approve, Burn, CloseAccount, Event, Events, Revoke,
+    SetAuthority, Signer, Transfer,
+};
+use substreams::{
+    scalar::BigInt,
+    store::{
+        StoreAdd, StoreAddBigInt, StoreDelete, StoreGet, StoreGetString, StoreNew, StoreSet,
+        StoreSetString,
+    },
+};
+
+/// Records the delegate of each token account, keyed `<account>:delegate`, set by `Approve`
+/// and removed by `Revoke`, by a change of the account owner or by closing the account.
+#[substreams::handlers::store]
+fn store_delegates(events: Events, store: StoreSetString) {
+    for (ordinal, event) in events.data.iter().enumerate() {
+        let ordinal = ordinal as u64;
+        if let Some(Type::Approve(Approve { accounts, .. })) = event.r#type.as_ref() {
+            let accounts = accounts.as_ref().unwrap();
+            store.set(ordinal, delegate_key(&accounts.source), &accounts.delegate);
+        } else if let Some(account) = delegation_cleared(event) {
+            store.delete_prefix(ordinal as i64, &delegate_key(account));
+        }
+    }
+}
+
+/// Tracks the remaining delegated amount of each token account, keyed
+/// `<account>:delegated_amount`. `Approve` resets it to the approved amount, and each
+/// `Transfer` or `Burn` signed by the current delegate of the source account consumes it.
+#[substreams::handlers::store]
+fn store_delegated_amount(events: Events, delegates: StoreGetString, store: StoreAddBigInt) {
+    for (ordinal, event) in events.data.iter().enumerate() {
+        let ordinal = ordinal as u64;
+        let Some(event_type) = event.r#type.as_ref() else {
+            continue;
+        };
+        let (source, amount, signer) = match event_type {
+            Type::Approve(Approve {
+                instruction,
+                accounts,
+            }) => {
+                let key = delegated_amount_key(&accounts.as_ref().unwrap().source);
+                store.delete_prefix(ordinal as i64, &key);
+                store.add(
+                    ordinal,
+                    key,
+                    BigInt::from(instruction.as_ref().unwrap().amount),
+                );
+                continue;
+            }
+            Type::Transfer(Transfer {
+                instruction,
+                accounts,
+                ..
+            }) => {
+                let accounts = accounts.as_ref().unwrap();
+                let amount = instruction.as_ref().unwrap().amount;
+                (&accounts.source, amount, accounts.signer.as_ref().unwrap())
+            }
+            Type::Burn(Burn {
+                instruction,
+                accounts,
+            }) => {
+                let accounts = accounts.as_ref().unwrap();
+                let amount = instruction.as_ref().unwrap().amount;
+                (&accounts.source, amount, accounts.signer.as_ref().unwrap())
+            }
+            _ => {
+                if let Some(account) = delegation_cleared(event) {
+                    store.delete_prefix(ordinal as i64, &delegated_amount_key(account));
+                }
+                continue;
+            }
+        };
+
+        if is_signed_by_delegate(&delegates, ordinal, source, signer) {
+            store.add(
+                ordinal,
+                delegated_amount_key(source),
+                BigInt::from(amount).neg(),
+            );
+        }
+    }
+}
+
+pub(crate) fn delegate_key(account: &str) -> String {
+    format!("{account}:delegate")
+}
+
+pub(crate) fn delegated_amount_key(account: &str) -> String {
+    format!("{account}:delegated_amount")
+}
+
+/// Returns the token account whose delegation the event removes, if any.
+pub(crate) fn delegation_cleared(event: &Event) -> Option<&str> {
+    match event.r#type.as_ref()? {
+        Type::Revoke(Revoke { accounts, .. }) => Some(&accounts.as_ref().unwrap().source),
+        Type::CloseAccount(CloseAccount { accounts, .. }) => {
+            Some(&accounts.as_ref().unwrap().account)
+        }
+        Type::SetAuthority(SetAuthority {
+            instruction,
+            accounts,
+        }) if instruction.as_ref().unwrap().authority_type()
+            == AuthorityType::AuthorityAccountOwner =>
+        {
+            Some(&accounts.as_ref().unwrap().account)
+        }
+        _ => None,
+    }
+}
+
+fn is_signed_by_delegate(
+    delegates: &StoreGetString,
+    ordinal: u64,
+    account: &str,
+    signer: &Signer,
+) -> bool {
+    delegates
+        .get_at(ordinal, delegate_key(account))
+        .is_some_and(|delegate| delegate == signer.authority())
+}
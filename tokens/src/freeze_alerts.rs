@@ -0,0 +1,73 @@
+use crate::pb::sf::solana::spl::token::v1::{
+    event::Type, Events, FreezeAccount, FreezeAlert, FreezeAlerts,
+};
+use anyhow::anyhow;
+use substreams::skip_empty_output;
+
+/// Raises an alert for each account frozen while holding at least `min_amount` tokens, the
+/// balance being taken from the balance changes of the freezing transaction.
+#[substreams::handlers::map]
+fn map_freeze_alerts(
+    params: String,
+    events: Events,
+) -> Result<FreezeAlerts, substreams::errors::Error> {
+    skip_empty_output();
+    let min_amount = min_amount_from_params(&params)?;
+
+    let mut data = Vec::new();
+    for event in &events.data {
+        let Some(Type::FreezeAccount(FreezeAccount { accounts, .. })) = event.r#type.as_ref()
+        else {
+            continue;
+        };
+        let accounts = accounts.as_ref().unwrap();
+        let Some(balance) = events
+            .balance_changes
+            .iter()
+            .find(|change| change.txn_id == event.txn_id && change.account == accounts.account)
+        else {
+            continue;
+        };
+
+        if balance.pre_amount < min_amount {
+            continue;
+        }
+
+        data.push(FreezeAlert {
+            txn_id: event.txn_id.clone(),
+            block_height: event.block_height,
+            block_timestamp: event.block_timestamp,
+            block_hash: event.block_hash.clone(),
+            instruction_index: event.instruction_index,
+            account: accounts.account.clone(),
+            owner: balance.owner.clone(),
+            mint: accounts.mint.clone(),
+            freeze_authority: accounts
+                .mint_freeze_authority
+                .as_ref()
+                .unwrap()
+                .authority()
+                .to_string(),
+            amount: balance.pre_amount,
+            decimals: balance.decimals,
+            slot: event.slot,
+            parent_slot: event.parent_slot,
+            previous_block_hash: event.previous_block_hash.clone(),
+        });
+    }
+
+    Ok(FreezeAlerts { data })
+}
+
+/// Parses params of the form `min_amount:<raw amount>`.
+fn min_amount_from_params(params: &str) -> Result<u64, substreams::errors::Error> {
+    let Some(("min_amount", min_amount)) = params.trim().split_once(':') else {
+        return Err(anyhow!(
+            "Invalid params {params:?}, must be of the form min_amount:<raw amount>"
+        ));
+    };
+
+    min_amount
+        .parse()
+        .map_err(|_| anyhow!("Invalid min_amount {min_amount:?}, must be a raw token amount"))
+}
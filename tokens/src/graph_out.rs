@@ -0,0 +1,226 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::delegations::delegation_cleared;
+use crate::pb::sf::solana::spl::token::v1::{
+    event::Type, set_authority::AuthorityType, Approve, CloseAccount, Event, Events, FreezeAccount,
+    InitializeAccount, InitializeMint, SetAuthority, Signer, ThawAccount, Transfer,
+};
+use substreams::{
+    pb::substreams::store_delta::Operation,
+    scalar::BigInt,
+    skip_empty_output,
+    store::{DeltaBigInt, Deltas},
+};
+use substreams_entity_change::{
+    pb::entity::EntityChanges,
+    tables::{Row, Tables},
+};
+
+#[substreams::handlers::map]
+fn graph_out(
+    events: Events,
+    mint_supply_deltas: Deltas<DeltaBigInt>,
+    delegated_amount_deltas: Deltas<DeltaBigInt>,
+) -> Result<EntityChanges, substreams::errors::Error> {
+    skip_empty_output();
+
+    let mut tables = Tables::new();
+    let mut approvals: HashMap<String, String> = HashMap::new();
+
+    for event in events.data {
+        let Some(event_type) = event.r#type.as_ref() else {
+            continue;
+        };
+
+        if let Some(account) = delegation_cleared(&event) {
+            tables
+                .update_row("TokenAccount", account)
+                .set("delegate", "")
+                .set("delegatedAmount", BigInt::from(0u64));
+        }
+
+        match event_type {
+            Type::Transfer(Transfer {
+                instruction,
+                accounts,
+                ..
+            }) => {
+                let accounts = accounts.as_ref().unwrap();
+
+                let row = create_event_row(&mut tables, "Transfer", &event)
+                    .set("amount", BigInt::from(instruction.as_ref().unwrap().amount))
+                    .set("source", &accounts.source)
+                    .set("destination", &accounts.destination)
+                    .set_signer(accounts.signer.as_ref().unwrap());
+                if let Some(token_mint) = &accounts.token_mint {
+                    row.set("mint", token_mint);
+                }
+            }
+            Type::Approve(Approve {
+                instruction,
+                accounts,
+            }) => {
+                let instruction = instruction.as_ref().unwrap();
+                let accounts = accounts.as_ref().unwrap();
+
+                let row = create_event_row(&mut tables, "Approval", &event)
+                    .set("account", &accounts.source)
+                    .set("delegate", &accounts.delegate)
+                    .set("amount", BigInt::from(instruction.amount))
+                    .set_signer(accounts.signer.as_ref().unwrap());
+                if let Some(token_mint) = &accounts.token_mint {
+                    row.set("mint", token_mint);
+                }
+
+                approvals.insert(accounts.source.clone(), accounts.delegate.clone());
+            }
+            Type::SetAuthority(SetAuthority {
+                instruction,
+                accounts,
+            }) => {
+                let instruction = instruction.as_ref().unwrap();
+                let accounts = accounts.as_ref().unwrap();
+                let new_authority = instruction.new_authority.clone().unwrap_or_default();
+
+                let row = create_event_row(&mut tables, "AuthorityChange", &event)
+                    .set("account", &accounts.account)
+                    .set("authorityType", instruction.authority_type().as_str_name())
+                    .set("currentAuthority", &accounts.current_authority);
+                if let Some(new_authority) = &instruction.new_authority {
+                    row.set("newAuthority", new_authority);
+                }
+
+                match instruction.authority_type() {
+                    AuthorityType::AuthorityMintTokens => {
+                        tables
+                            .update_row("Mint", &accounts.account)
+                            .set("mintAuthority", new_authority);
+                    }
+                    AuthorityType::AuthorityFreezeAccount => {
+                        tables
+                            .update_row("Mint", &accounts.account)
+                            .set("freezeAuthority", new_authority);
+                    }
+                    AuthorityType::AuthorityAccountOwner => {
+                        tables
+                            .update_row("TokenAccount", &accounts.account)
+                            .set("owner", new_authority);
+                    }
+                    AuthorityType::AuthorityCloseAccount => {
+                        tables
+                            .update_row("TokenAccount", &accounts.account)
+                            .set("closeAuthority", new_authority);
+                    }
+                    AuthorityType::AuthorityNull => {}
+                }
+            }
+            Type::InitializeMint(InitializeMint {
+                instruction,
+                accounts,
+                ..
+            }) => {
+                let instruction = instruction.as_ref().unwrap();
+
+                tables
+                    .create_row("Mint", &accounts.as_ref().unwrap().mint)
+                    .set("decimals", instruction.decimals as i32)
+                    .set("mintAuthority", &instruction.mint_authority)
+                    .set(
+                        "freezeAuthority",
+                        instruction.freeze_authority.clone().unwrap_or_default(),
+                    )
+                    .set("supply", BigInt::from(0u64));
+            }
+            Type::InitializeAccount(InitializeAccount {
+                accounts,
+                is_associated_account,
+                ..
+            }) => {
+                let accounts = accounts.as_ref().unwrap();
+
+                tables
+                    .create_row("TokenAccount", &accounts.account)
+                    .set("owner", &accounts.owner)
+                    .set("mint", &accounts.mint)
+                    .set("isAssociatedAccount", *is_associated_account)
+                    .set("delegate", "")
+                    .set("delegatedAmount", BigInt::from(0u64))
+                    .set("closeAuthority", "")
+                    .set("frozen", false)
+                    .set("closed", false);
+            }
+            Type::FreezeAccount(FreezeAccount { accounts, .. }) => {
+                tables
+                    .update_row("TokenAccount", &accounts.as_ref().unwrap().account)
+                    .set("frozen", true);
+            }
+            Type::ThawAccount(ThawAccount { accounts, .. }) => {
+                tables
+                    .update_row("TokenAccount", &accounts.as_ref().unwrap().account)
+                    .set("frozen", false);
+            }
+            Type::CloseAccount(CloseAccount { accounts, .. }) => {
+                tables
+                    .update_row("TokenAccount", &accounts.as_ref().unwrap().account)
+                    .set("closed", true);
+            }
+            _ => {}
+        }
+    }
+
+    for delta in mint_supply_deltas.deltas {
+        tables
+            .update_row("Mint", &delta.key)
+            .set("supply", delta.new_value);
+    }
+
+    // Approvals reset the delegated amount, and transfers and burns signed by the delegate
+    // consume it, the delegate being cleared once the whole allowance is consumed, as in
+    // `store_delegated_amount`. The last delta of an account tells its allowance at the end of
+    // the block, a deletion being written by the event that cleared the delegation.
+    let mut accounts: HashSet<String> = HashSet::new();
+    for delta in delegated_amount_deltas.deltas.into_iter().rev() {
+        let Some(account) = delta.key.strip_suffix(":delegated_amount") else {
+            continue;
+        };
+        if !accounts.insert(account.to_string()) || delta.operation == Operation::Delete {
+            continue;
+        }
+
+        let row = tables.update_row("TokenAccount", account);
+        if delta.new_value.is_zero() {
+            row.set("delegate", "");
+        } else if let Some(delegate) = approvals.get(account) {
+            row.set("delegate", delegate);
+        }
+        row.set("delegatedAmount", delta.new_value);
+    }
+
+    Ok(tables.to_entity_changes())
+}
+
+/// Creates the entity of the event, identified by `<txn_id>-<instruction_index>`, with the
+/// common transaction and block fields set.
+fn create_event_row<'a>(tables: &'a mut Tables, entity: &str, event: &Event) -> &'a mut Row {
+    tables
+        .create_row(
+            entity,
+            format!("{}-{}", event.txn_id, event.instruction_index),
+        )
+        .set("transaction", &event.txn_id)
+        .set("instructionIndex", event.instruction_index as i32)
+        .set("blockNumber", BigInt::from(event.slot))
+        .set("blockHash", &event.block_hash)
+        .set("timestamp", BigInt::from(event.block_timestamp))
+}
+
+trait SetSigner {
+    fn set_signer(&mut self, signer: &Signer) -> &mut Row;
+}
+
+impl SetSigner for Row {
+    fn set_signer(&mut self, signer: &Signer) -> &mut Row {
+        self.set("authority", signer.authority())
+            .set("signers", signer.signing_keys())
+    }
+}
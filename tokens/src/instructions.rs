@@ -1,12 +1,15 @@
 use anyhow::anyhow;
 use spl_token::instruction::{AuthorityType as SplAuthorityType, TokenInstruction};
-use substreams_solana::{block_view::InstructionView, Address};
+use substreams_solana::{
+    block_view::InstructionView, pb::sf::solana::r#type::v1::ConfirmedTransaction, Address,
+};
 
+use crate::associated_account::{account_at, ASSOCIATED_TOKEN_ACCOUNT_PROGRAM_ID};
 use crate::pb::sf::solana::spl::token::v1::{
     approve::{ApproveAccounts, ApproveInstruction},
     burn::{BurnAccounts, BurnInstruction},
     close_account::{CloseAccountAccounts, CloseAccountInstruction},
-    event::Type,
+    event::{InstructionKind, Type},
     freeze_account::{FreezeAccountAccounts, FreezeAccountInstruction},
     initialize_account::{
         InitializeAccountAccounts, InitializeAccountInstruction, InitializeAccountVersion,
@@ -20,7 +23,7 @@ use crate::pb::sf::solana::spl::token::v1::{
     },
     mint_to::{MintToAccounts, MintToInstruction},
     revoke::{RevokeAccounts, RevokeInstruction},
-    set_authority::{AuthorityType, SetAuthorityAccounts, SetAuthorityInstruction},
+    set_authority::{AccountKind, AuthorityType, SetAuthorityAccounts, SetAuthorityInstruction},
     signer::Kind as SignerKind,
     sync_native::{SyncNativeAccounts, SyncNativeInstruction},
     thaw_account::{ThawAccountAccounts, ThawAccountInstruction},
@@ -30,11 +33,109 @@ use crate::pb::sf::solana::spl::token::v1::{
     SingleSignature, SyncNative, ThawAccount, Transfer,
 };
 
-impl TryFrom<(TokenInstruction<'_>, &InstructionView<'_>)> for Type {
+/// Decodes an instruction of the SPL Token or Associated Token Account program, `None` being
+/// returned for the instructions of any other program and the ones that aren't modeled. The
+/// `signers` of its transaction tell a multisig authority apart from a single one. Fails on a
+/// SPL Token instruction that can't be unpacked.
+pub fn decode_instruction(
+    instruction: &InstructionView<'_>,
+    signers: &TransactionSigners,
+) -> Result<Option<Type>, substreams::errors::Error> {
+    if instruction.program_id() == spl_token::ID {
+        let token_instruction = TokenInstruction::unpack(instruction.data())?;
+
+        Ok(Type::try_from((token_instruction, instruction, signers)).ok())
+    } else if instruction.program_id() == ASSOCIATED_TOKEN_ACCOUNT_PROGRAM_ID {
+        Ok(Type::try_from(instruction).ok())
+    } else {
+        Ok(None)
+    }
+}
+
+impl InstructionKind {
+    /// The kind of an instruction of the SPL Token or Associated Token Account program, read
+    /// from its discriminator. The instructions of any other program and the unknown
+    /// discriminators are `Unspecified`.
+    pub fn from_instruction(instruction: &InstructionView<'_>) -> Self {
+        let discriminator = instruction.data().first().copied();
+
+        let kind = if instruction.program_id() == spl_token::ID {
+            discriminator
+                .map(|discriminator| discriminator as i32 + 1)
+                .filter(|kind| *kind <= InstructionKind::UiAmountToAmount as i32)
+                .and_then(InstructionKind::from_i32)
+        } else if instruction.program_id() == ASSOCIATED_TOKEN_ACCOUNT_PROGRAM_ID {
+            match discriminator {
+                None | Some(0) => Some(InstructionKind::AssociatedAccountCreate),
+                Some(1) => Some(InstructionKind::AssociatedAccountCreateIdempotent),
+                Some(2) => Some(InstructionKind::AssociatedAccountRecoverNested),
+                Some(_) => None,
+            }
+        } else {
+            None
+        };
+
+        kind.unwrap_or(InstructionKind::Unspecified)
+    }
+}
+
+/// The keys signing a transaction, that is the first `num_required_signatures` account keys of
+/// its message.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TransactionSigners(Vec<String>);
+
+impl TransactionSigners {
+    pub fn new(trx: &ConfirmedTransaction) -> Self {
+        let Some(message) = trx
+            .transaction
+            .as_ref()
+            .and_then(|transaction| transaction.message.as_ref())
+        else {
+            return Self::default();
+        };
+        let signer_count = message
+            .header
+            .as_ref()
+            .map(|header| header.num_required_signatures as usize)
+            .unwrap_or_default();
+
+        Self(
+            message
+                .account_keys
+                .iter()
+                .take(signer_count)
+                .map(|key| bs58::encode(key).into_string())
+                .collect(),
+        )
+    }
+
+    fn contains(&self, account: &Address) -> bool {
+        let account = account.to_string();
+        self.0.contains(&account)
+    }
+
+    pub(crate) fn into_vec(self) -> Vec<String> {
+        self.0
+    }
+}
+
+impl
+    TryFrom<(
+        TokenInstruction<'_>,
+        &InstructionView<'_>,
+        &TransactionSigners,
+    )> for Type
+{
     type Error = substreams::errors::Error;
 
-    fn try_from(value: (TokenInstruction<'_>, &InstructionView<'_>)) -> Result<Self, Self::Error> {
-        let (value, instruction_view) = value;
+    fn try_from(
+        value: (
+            TokenInstruction<'_>,
+            &InstructionView<'_>,
+            &TransactionSigners,
+        ),
+    ) -> Result<Self, Self::Error> {
+        let (value, instruction_view, signers) = value;
         let accounts = instruction_view.accounts();
 
         Ok(match value {
@@ -44,11 +145,12 @@ impl TryFrom<(TokenInstruction<'_>, &InstructionView<'_>)> for Type {
                     decimals: None,
                 }),
                 accounts: Some(TransferAccounts {
-                    source: accounts.get(0).unwrap().to_string(),
-                    destination: accounts.get(1).unwrap().to_string(),
-                    signer: new_signer_at(&accounts, 2),
+                    source: account_at(&accounts, 0)?,
+                    destination: account_at(&accounts, 1)?,
+                    signer: new_signer_at(&accounts, signers, 2)?,
                     token_mint: None,
                 }),
+                memo: None,
             }),
             TokenInstruction::InitializeMint {
                 mint_authority,
@@ -64,17 +166,18 @@ impl TryFrom<(TokenInstruction<'_>, &InstructionView<'_>)> for Type {
                     decimals: decimals as u32,
                 }),
                 accounts: Some(InitializeMintAccounts {
-                    mint: accounts.get(0).unwrap().to_string(),
+                    mint: account_at(&accounts, 0)?,
                 }),
             }),
             TokenInstruction::InitializeAccount => Type::InitializeAccount(InitializeAccount {
                 version: InitializeAccountVersion::V1 as i32,
                 instruction: Some(InitializeAccountInstruction {}),
                 accounts: Some(InitializeAccountAccounts {
-                    account: accounts.get(0).unwrap().to_string(),
-                    mint: accounts.get(1).unwrap().to_string(),
-                    owner: accounts.get(2).unwrap().to_string(),
+                    account: account_at(&accounts, 0)?,
+                    mint: account_at(&accounts, 1)?,
+                    owner: account_at(&accounts, 2)?,
                 }),
+                is_associated_account: false,
             }),
             TokenInstruction::InitializeMultisig { m } => {
                 Type::InitializeMultisig(InitializeMultisig {
@@ -83,8 +186,9 @@ impl TryFrom<(TokenInstruction<'_>, &InstructionView<'_>)> for Type {
                         signature_count_threshold: m as u32,
                     }),
                     accounts: Some(InitializeMultisigAccounts {
-                        account: accounts.get(0).unwrap().to_string(),
-                        signers: accounts[1..].iter().map(|a| a.to_string()).collect(),
+                        account: account_at(&accounts, 0)?,
+                        // The second account is the rent sysvar
+                        signers: accounts.iter().skip(2).map(|a| a.to_string()).collect(),
                     }),
                 })
             }
@@ -94,33 +198,17 @@ impl TryFrom<(TokenInstruction<'_>, &InstructionView<'_>)> for Type {
                     decimals: None,
                 }),
                 accounts: Some(ApproveAccounts {
-                    source: accounts.get(0).unwrap().to_string(),
-                    delegate: accounts.get(1).unwrap().to_string(),
-                    signer: new_signer(match accounts.len() {
-                        3 => SignerKind::Single(SingleSignature {
-                            signer: accounts.get(2).unwrap().to_string(),
-                        }),
-                        _ => SignerKind::Multisig(MultiSignature {
-                            multisig_account: accounts.get(2).unwrap().to_string(),
-                            signers: accounts[3..].iter().map(Address::to_string).collect(),
-                        }),
-                    }),
+                    source: account_at(&accounts, 0)?,
+                    delegate: account_at(&accounts, 1)?,
+                    signer: new_signer_at(&accounts, signers, 2)?,
                     token_mint: None,
                 }),
             }),
             TokenInstruction::Revoke => Type::Revoke(Revoke {
                 instruction: Some(RevokeInstruction {}),
                 accounts: Some(RevokeAccounts {
-                    source: accounts.get(0).unwrap().to_string(),
-                    signer: new_signer(match accounts.len() {
-                        2 => SignerKind::Single(SingleSignature {
-                            signer: accounts.get(1).unwrap().to_string(),
-                        }),
-                        _ => SignerKind::Multisig(MultiSignature {
-                            multisig_account: accounts.get(1).unwrap().to_string(),
-                            signers: accounts[2..].iter().map(Address::to_string).collect(),
-                        }),
-                    }),
+                    source: account_at(&accounts, 0)?,
+                    signer: new_signer_at(&accounts, signers, 1)?,
                 }),
             }),
             TokenInstruction::SetAuthority {
@@ -139,8 +227,17 @@ impl TryFrom<(TokenInstruction<'_>, &InstructionView<'_>)> for Type {
                         .unwrap_or_default(),
                 }),
                 accounts: Some(SetAuthorityAccounts {
-                    account: accounts.get(0).unwrap().to_string(),
-                    current_authority: accounts.get(1).unwrap().to_string(),
+                    account: account_at(&accounts, 0)?,
+                    current_authority: account_at(&accounts, 1)?,
+                    signer: new_signer_at(&accounts, signers, 1)?,
+                    account_kind: match authority_type {
+                        SplAuthorityType::MintTokens | SplAuthorityType::FreezeAccount => {
+                            AccountKind::Mint
+                        }
+                        SplAuthorityType::AccountOwner | SplAuthorityType::CloseAccount => {
+                            AccountKind::TokenAccount
+                        }
+                    } as i32,
                 }),
             }),
             TokenInstruction::MintTo { amount } => Type::MintTo(MintTo {
@@ -149,17 +246,9 @@ impl TryFrom<(TokenInstruction<'_>, &InstructionView<'_>)> for Type {
                     decimals: None,
                 }),
                 accounts: Some(MintToAccounts {
-                    mint: accounts.get(0).unwrap().to_string(),
-                    destination: accounts.get(1).unwrap().to_string(),
-                    mint_authority: new_signer(match accounts.len() {
-                        3 => SignerKind::Single(SingleSignature {
-                            signer: accounts.get(2).unwrap().to_string(),
-                        }),
-                        _ => SignerKind::Multisig(MultiSignature {
-                            multisig_account: accounts.get(2).unwrap().to_string(),
-                            signers: accounts[3..].iter().map(Address::to_string).collect(),
-                        }),
-                    }),
+                    mint: account_at(&accounts, 0)?,
+                    destination: account_at(&accounts, 1)?,
+                    mint_authority: new_signer_at(&accounts, signers, 2)?,
                 }),
             }),
             TokenInstruction::Burn { amount } => Type::Burn(Burn {
@@ -168,65 +257,36 @@ impl TryFrom<(TokenInstruction<'_>, &InstructionView<'_>)> for Type {
                     decimals: None,
                 }),
                 accounts: Some(BurnAccounts {
-                    source: accounts.get(0).unwrap().to_string(),
-                    mint: accounts.get(1).unwrap().to_string(),
-                    signer: new_signer(match accounts.len() {
-                        3 => SignerKind::Single(SingleSignature {
-                            signer: accounts.get(2).unwrap().to_string(),
-                        }),
-                        _ => SignerKind::Multisig(MultiSignature {
-                            multisig_account: accounts.get(2).unwrap().to_string(),
-                            signers: accounts[3..].iter().map(Address::to_string).collect(),
-                        }),
-                    }),
+                    source: account_at(&accounts, 0)?,
+                    mint: account_at(&accounts, 1)?,
+                    signer: new_signer_at(&accounts, signers, 2)?,
                 }),
             }),
             TokenInstruction::CloseAccount => Type::CloseAccount(CloseAccount {
                 instruction: Some(CloseAccountInstruction {}),
                 accounts: Some(CloseAccountAccounts {
-                    account: accounts.get(0).unwrap().to_string(),
-                    destination: accounts.get(1).unwrap().to_string(),
-                    signer: new_signer(match accounts.len() {
-                        3 => SignerKind::Single(SingleSignature {
-                            signer: accounts.get(2).unwrap().to_string(),
-                        }),
-                        _ => SignerKind::Multisig(MultiSignature {
-                            multisig_account: accounts.get(2).unwrap().to_string(),
-                            signers: accounts[3..].iter().map(Address::to_string).collect(),
-                        }),
-                    }),
+                    account: account_at(&accounts, 0)?,
+                    destination: account_at(&accounts, 1)?,
+                    signer: new_signer_at(&accounts, signers, 2)?,
                 }),
+                mint: String::new(),
+                amount: 0,
+                reclaimed_lamports: 0,
             }),
             TokenInstruction::FreezeAccount => Type::FreezeAccount(FreezeAccount {
                 instruction: Some(FreezeAccountInstruction {}),
                 accounts: Some(FreezeAccountAccounts {
-                    account: accounts.get(0).unwrap().to_string(),
-                    mint: accounts.get(1).unwrap().to_string(),
-                    mint_freeze_authority: new_signer(match accounts.len() {
-                        3 => SignerKind::Single(SingleSignature {
-                            signer: accounts.get(2).unwrap().to_string(),
-                        }),
-                        _ => SignerKind::Multisig(MultiSignature {
-                            multisig_account: accounts.get(2).unwrap().to_string(),
-                            signers: accounts[3..].iter().map(Address::to_string).collect(),
-                        }),
-                    }),
+                    account: account_at(&accounts, 0)?,
+                    mint: account_at(&accounts, 1)?,
+                    mint_freeze_authority: new_signer_at(&accounts, signers, 2)?,
                 }),
             }),
             TokenInstruction::ThawAccount => Type::ThawAccount(ThawAccount {
                 instruction: Some(ThawAccountInstruction {}),
                 accounts: Some(ThawAccountAccounts {
-                    account: accounts.get(0).unwrap().to_string(),
-                    mint: accounts.get(1).unwrap().to_string(),
-                    mint_freeze_authority: new_signer(match accounts.len() {
-                        3 => SignerKind::Single(SingleSignature {
-                            signer: accounts.get(2).unwrap().to_string(),
-                        }),
-                        _ => SignerKind::Multisig(MultiSignature {
-                            multisig_account: accounts.get(2).unwrap().to_string(),
-                            signers: accounts[3..].iter().map(Address::to_string).collect(),
-                        }),
-                    }),
+                    account: account_at(&accounts, 0)?,
+                    mint: account_at(&accounts, 1)?,
+                    mint_freeze_authority: new_signer_at(&accounts, signers, 2)?,
                 }),
             }),
             TokenInstruction::TransferChecked { amount, decimals } => Type::Transfer(Transfer {
@@ -235,19 +295,12 @@ impl TryFrom<(TokenInstruction<'_>, &InstructionView<'_>)> for Type {
                     decimals: Some(decimals as u32),
                 }),
                 accounts: Some(TransferAccounts {
-                    source: accounts.get(0).unwrap().to_string(),
-                    token_mint: Some(accounts.get(1).unwrap().to_string()),
-                    destination: accounts.get(2).unwrap().to_string(),
-                    signer: new_signer(match accounts.len() {
-                        4 => SignerKind::Single(SingleSignature {
-                            signer: accounts.get(3).unwrap().to_string(),
-                        }),
-                        _ => SignerKind::Multisig(MultiSignature {
-                            multisig_account: accounts.get(3).unwrap().to_string(),
-                            signers: accounts[4..].iter().map(Address::to_string).collect(),
-                        }),
-                    }),
+                    source: account_at(&accounts, 0)?,
+                    token_mint: Some(account_at(&accounts, 1)?),
+                    destination: account_at(&accounts, 2)?,
+                    signer: new_signer_at(&accounts, signers, 3)?,
                 }),
+                memo: None,
             }),
             TokenInstruction::ApproveChecked { amount, decimals } => Type::Approve(Approve {
                 instruction: Some(ApproveInstruction {
@@ -255,18 +308,10 @@ impl TryFrom<(TokenInstruction<'_>, &InstructionView<'_>)> for Type {
                     decimals: Some(decimals as u32),
                 }),
                 accounts: Some(ApproveAccounts {
-                    source: accounts.get(0).unwrap().to_string(),
-                    token_mint: Some(accounts.get(1).unwrap().to_string()),
-                    delegate: accounts.get(2).unwrap().to_string(),
-                    signer: new_signer(match accounts.len() {
-                        4 => SignerKind::Single(SingleSignature {
-                            signer: accounts.get(3).unwrap().to_string(),
-                        }),
-                        _ => SignerKind::Multisig(MultiSignature {
-                            multisig_account: accounts.get(3).unwrap().to_string(),
-                            signers: accounts[4..].iter().map(Address::to_string).collect(),
-                        }),
-                    }),
+                    source: account_at(&accounts, 0)?,
+                    token_mint: Some(account_at(&accounts, 1)?),
+                    delegate: account_at(&accounts, 2)?,
+                    signer: new_signer_at(&accounts, signers, 3)?,
                 }),
             }),
             TokenInstruction::MintToChecked { amount, decimals } => Type::MintTo(MintTo {
@@ -275,17 +320,9 @@ impl TryFrom<(TokenInstruction<'_>, &InstructionView<'_>)> for Type {
                     decimals: Some(decimals as u32),
                 }),
                 accounts: Some(MintToAccounts {
-                    mint: accounts.get(0).unwrap().to_string(),
-                    destination: accounts.get(1).unwrap().to_string(),
-                    mint_authority: new_signer(match accounts.len() {
-                        3 => SignerKind::Single(SingleSignature {
-                            signer: accounts.get(2).unwrap().to_string(),
-                        }),
-                        _ => SignerKind::Multisig(MultiSignature {
-                            multisig_account: accounts.get(2).unwrap().to_string(),
-                            signers: accounts[3..].iter().map(Address::to_string).collect(),
-                        }),
-                    }),
+                    mint: account_at(&accounts, 0)?,
+                    destination: account_at(&accounts, 1)?,
+                    mint_authority: new_signer_at(&accounts, signers, 2)?,
                 }),
             }),
             TokenInstruction::BurnChecked { amount, decimals } => Type::Burn(Burn {
@@ -294,17 +331,9 @@ impl TryFrom<(TokenInstruction<'_>, &InstructionView<'_>)> for Type {
                     decimals: Some(decimals as u32),
                 }),
                 accounts: Some(BurnAccounts {
-                    source: accounts.get(0).unwrap().to_string(),
-                    mint: accounts.get(1).unwrap().to_string(),
-                    signer: new_signer(match accounts.len() {
-                        3 => SignerKind::Single(SingleSignature {
-                            signer: accounts.get(2).unwrap().to_string(),
-                        }),
-                        _ => SignerKind::Multisig(MultiSignature {
-                            multisig_account: accounts.get(2).unwrap().to_string(),
-                            signers: accounts[3..].iter().map(Address::to_string).collect(),
-                        }),
-                    }),
+                    source: account_at(&accounts, 0)?,
+                    mint: account_at(&accounts, 1)?,
+                    signer: new_signer_at(&accounts, signers, 2)?,
                 }),
             }),
             TokenInstruction::InitializeAccount2 { owner } => {
@@ -312,16 +341,17 @@ impl TryFrom<(TokenInstruction<'_>, &InstructionView<'_>)> for Type {
                     version: InitializeAccountVersion::V2 as i32,
                     instruction: Some(InitializeAccountInstruction {}),
                     accounts: Some(InitializeAccountAccounts {
-                        account: accounts.get(0).unwrap().to_string(),
-                        mint: accounts.get(1).unwrap().to_string(),
+                        account: account_at(&accounts, 0)?,
+                        mint: account_at(&accounts, 1)?,
                         owner: owner.to_string(),
                     }),
+                    is_associated_account: false,
                 })
             }
             TokenInstruction::SyncNative => Type::SyncNative(SyncNative {
                 instruction: Some(SyncNativeInstruction {}),
                 accounts: Some(SyncNativeAccounts {
-                    native_token_account: accounts.get(0).unwrap().to_string(),
+                    native_token_account: account_at(&accounts, 0)?,
                 }),
             }),
             TokenInstruction::InitializeAccount3 { owner } => {
@@ -329,10 +359,11 @@ impl TryFrom<(TokenInstruction<'_>, &InstructionView<'_>)> for Type {
                     version: InitializeAccountVersion::V3 as i32,
                     instruction: Some(InitializeAccountInstruction {}),
                     accounts: Some(InitializeAccountAccounts {
-                        account: accounts.get(0).unwrap().to_string(),
-                        mint: accounts.get(1).unwrap().to_string(),
+                        account: account_at(&accounts, 0)?,
+                        mint: account_at(&accounts, 1)?,
                         owner: owner.to_string(),
                     }),
+                    is_associated_account: false,
                 })
             }
             TokenInstruction::InitializeMultisig2 { m } => {
@@ -342,8 +373,8 @@ impl TryFrom<(TokenInstruction<'_>, &InstructionView<'_>)> for Type {
                         signature_count_threshold: m as u32,
                     }),
                     accounts: Some(InitializeMultisigAccounts {
-                        account: accounts.get(0).unwrap().to_string(),
-                        signers: accounts[1..].iter().map(|a| a.to_string()).collect(),
+                        account: account_at(&accounts, 0)?,
+                        signers: accounts.iter().skip(1).map(|a| a.to_string()).collect(),
                     }),
                 })
             }
@@ -352,7 +383,7 @@ impl TryFrom<(TokenInstruction<'_>, &InstructionView<'_>)> for Type {
                 mint_authority,
                 freeze_authority,
             } => Type::InitializeMint(InitializeMint {
-                version: InitializeMintVersion::V1 as i32,
+                version: InitializeMintVersion::V2 as i32,
                 instruction: Some(InitializeMintInstruction {
                     mint_authority: mint_authority.to_string(),
                     freeze_authority: freeze_authority
@@ -361,25 +392,25 @@ impl TryFrom<(TokenInstruction<'_>, &InstructionView<'_>)> for Type {
                     decimals: decimals as u32,
                 }),
                 accounts: Some(InitializeMintAccounts {
-                    mint: accounts.get(0).unwrap().to_string(),
+                    mint: account_at(&accounts, 0)?,
                 }),
             }),
             TokenInstruction::InitializeImmutableOwner => {
                 Type::InitializeImmutableOwner(InitializeImmutableOwner {
                     instruction: Some(InitializeImmutableOwnerInstruction {}),
                     accounts: Some(InitializeImmutableOwnerAccounts {
-                        account: accounts.get(0).unwrap().to_string(),
+                        account: account_at(&accounts, 0)?,
                     }),
                 })
             }
             TokenInstruction::GetAccountDataSize => {
-                return Err(anyhow!("GetAccountDataSize is not supported").into())
+                return Err(anyhow!("GetAccountDataSize is not supported"))
             }
             TokenInstruction::AmountToUiAmount { .. } => {
-                return Err(anyhow!("GetAccountDataSize is not supported").into())
+                return Err(anyhow!("AmountToUiAmount is not supported"))
             }
             TokenInstruction::UiAmountToAmount { .. } => {
-                return Err(anyhow!("GetAccountDataSize is not supported").into())
+                return Err(anyhow!("UiAmountToAmount is not supported"))
             }
         })
     }
@@ -391,43 +422,135 @@ fn single_signer(signer: &Address) -> SignerKind {
     })
 }
 
-fn multi_signers<'a>(multisig: &Address, signers: &'a [Address]) -> SignerKind {
+fn multi_signers(multisig: &Address, signers: Vec<String>) -> SignerKind {
     SignerKind::Multisig(MultiSignature {
         multisig_account: multisig.to_string(),
-        signers: signers.iter().map(|a| a.to_string()).collect(),
+        signers,
+        ..Default::default()
     })
 }
 
-fn new_signer_at(accounts: &Vec<Address>, at: usize) -> Option<Signer> {
-    Some(Signer {
-        kind: Some(if accounts.len() == at + 1 {
-            single_signer(accounts.get(at).expect("single signer at index {}"))
-        } else {
-            multi_signers(
-                accounts.get(at).expect("multi signer at index {}"),
-                &accounts[at + 1..],
-            )
-        }),
-    })
+/// Decodes the authority at index `at` of the instruction accounts from the `signers` of the
+/// transaction. A multisig account can't sign, so an authority signing the transaction is a
+/// single signer, any account following it being an extra account of the instruction. An
+/// authority that doesn't sign is a multisig account when some of the accounts following it
+/// sign the transaction, those being its signers, and is otherwise a single signer signing
+/// through the program invoking the instruction, as a program derived address does. Fails when
+/// the instruction has no account at index `at`.
+fn new_signer_at(
+    accounts: &[Address],
+    signers: &TransactionSigners,
+    at: usize,
+) -> Result<Option<Signer>, substreams::errors::Error> {
+    let authority = accounts
+        .get(at)
+        .ok_or_else(|| anyhow!("Missing authority at index {}", at))?;
+    let multisig_signers: Vec<String> = accounts[at + 1..]
+        .iter()
+        .filter(|account| signers.contains(account))
+        .map(|account| account.to_string())
+        .collect();
+
+    Ok(Some(Signer {
+        kind: Some(
+            if signers.contains(authority) || multisig_signers.is_empty() {
+                single_signer(authority)
+            } else {
+                multi_signers(authority, multisig_signers)
+            },
+        ),
+    }))
+}
+
+impl Signer {
+    /// The authority of the instruction, that is the single signer or the multisig account.
+    pub(crate) fn authority(&self) -> &str {
+        match self.kind.as_ref().unwrap() {
+            SignerKind::Single(single) => &single.signer,
+            SignerKind::Multisig(multi) => &multi.multisig_account,
+        }
+    }
+
+    /// The keys that actually signed the instruction.
+    pub(crate) fn signing_keys(&self) -> Vec<String> {
+        match self.kind.as_ref().unwrap() {
+            SignerKind::Single(single) => vec![single.signer.clone()],
+            SignerKind::Multisig(multi) => multi.signers.clone(),
+        }
+    }
+
+    /// The multisig account of the authority, empty for a single signer.
+    pub(crate) fn multisig_account(&self) -> &str {
+        match self.kind.as_ref().unwrap() {
+            SignerKind::Single(_) => "",
+            SignerKind::Multisig(multi) => &multi.multisig_account,
+        }
+    }
 }
 
-fn new_signer(kind: SignerKind) -> Option<Signer> {
-    Some(Signer { kind: Some(kind) })
+impl Type {
+    /// The signer of the instruction, `None` for the instructions that don't require one.
+    pub(crate) fn signer(&self) -> Option<&Signer> {
+        match self {
+            Type::Transfer(v) => v.accounts.as_ref()?.signer.as_ref(),
+            Type::Approve(v) => v.accounts.as_ref()?.signer.as_ref(),
+            Type::Revoke(v) => v.accounts.as_ref()?.signer.as_ref(),
+            Type::SetAuthority(v) => v.accounts.as_ref()?.signer.as_ref(),
+            Type::MintTo(v) => v.accounts.as_ref()?.mint_authority.as_ref(),
+            Type::Burn(v) => v.accounts.as_ref()?.signer.as_ref(),
+            Type::CloseAccount(v) => v.accounts.as_ref()?.signer.as_ref(),
+            Type::FreezeAccount(v) => v.accounts.as_ref()?.mint_freeze_authority.as_ref(),
+            Type::ThawAccount(v) => v.accounts.as_ref()?.mint_freeze_authority.as_ref(),
+            Type::InitializeMint(_)
+            | Type::InitializeImmutableOwner(_)
+            | Type::InitializeAccount(_)
+            | Type::InitializeMultisig(_)
+            | Type::SyncNative(_)
+            | Type::AssociatedAccountCreated(_)
+            | Type::AssociatedAccountNestedRecovered(_) => None,
+        }
+    }
+
+    /// The mutable counterpart of [Type::signer].
+    pub(crate) fn signer_mut(&mut self) -> Option<&mut Signer> {
+        match self {
+            Type::Transfer(v) => v.accounts.as_mut()?.signer.as_mut(),
+            Type::Approve(v) => v.accounts.as_mut()?.signer.as_mut(),
+            Type::Revoke(v) => v.accounts.as_mut()?.signer.as_mut(),
+            Type::SetAuthority(v) => v.accounts.as_mut()?.signer.as_mut(),
+            Type::MintTo(v) => v.accounts.as_mut()?.mint_authority.as_mut(),
+            Type::Burn(v) => v.accounts.as_mut()?.signer.as_mut(),
+            Type::CloseAccount(v) => v.accounts.as_mut()?.signer.as_mut(),
+            Type::FreezeAccount(v) => v.accounts.as_mut()?.mint_freeze_authority.as_mut(),
+            Type::ThawAccount(v) => v.accounts.as_mut()?.mint_freeze_authority.as_mut(),
+            Type::InitializeMint(_)
+            | Type::InitializeImmutableOwner(_)
+            | Type::InitializeAccount(_)
+            | Type::InitializeMultisig(_)
+            | Type::SyncNative(_)
+            | Type::AssociatedAccountCreated(_)
+            | Type::AssociatedAccountNestedRecovered(_) => None,
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use substreams_solana::Address;
 
+    use super::TransactionSigners;
+
     #[test]
     fn test_new_signer_at() {
-        let addresses: Vec<_> = vec!["B".to_string(), "C".to_string(), "D".to_string()]
+        let addresses: Vec<_> = ["B", "C", "D", "E"]
             .iter()
             .map(|data| bs58::decode(data).into_vec().unwrap())
             .collect();
+        let accounts: Vec<Address> = addresses.iter().map(Address).collect();
+        let signers = TransactionSigners(vec!["D".to_string()]);
 
-        let accounts = addresses.iter().map(|a| Address(a)).collect();
-        let signer = super::new_signer_at(&accounts, 2);
+        // A signing authority followed by an extra account
+        let signer = super::new_signer_at(&accounts, &signers, 2).unwrap();
         assert_eq!(
             signer,
             Some(super::Signer {
@@ -437,15 +560,30 @@ mod tests {
             })
         );
 
-        let signer = super::new_signer_at(&accounts, 1);
+        let signer = super::new_signer_at(&accounts, &signers, 1).unwrap();
         assert_eq!(
             signer,
             Some(super::Signer {
                 kind: Some(super::SignerKind::Multisig(super::MultiSignature {
                     multisig_account: "C".to_string(),
-                    signers: vec!["D".to_string()]
+                    signers: vec!["D".to_string()],
+                    ..Default::default()
+                }))
+            })
+        );
+
+        // An authority signing through the invoking program
+        let signer = super::new_signer_at(&accounts, &TransactionSigners::default(), 1).unwrap();
+        assert_eq!(
+            signer,
+            Some(super::Signer {
+                kind: Some(super::SignerKind::Single(super::SingleSignature {
+                    signer: "C".to_string()
                 }))
             })
         );
+
+        // A malformed instruction missing its authority
+        assert!(super::new_signer_at(&accounts, &signers, 4).is_err());
     }
 }
@@ -0,0 +1,296 @@
+//! The canonical JSON shape of the `pb` types, enabled by the `serde` feature and described by
+//! `events.schema.json`. 64-bit integers are strings, as most JSON parsers can't represent
+//! them exactly, enums are the names of their values and bytes are base58 encoded.
+
+use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+use crate::pb::sf::solana::spl::token::v1::{
+    event::InstructionKind,
+    initialize_account::InitializeAccountVersion,
+    initialize_mint::InitializeMintVersion,
+    initialize_multisig::InitializeMultisigVersion,
+    set_authority::{AccountKind, AuthorityType},
+    wrapped_sol_event::Kind as WrappedSolKind,
+};
+
+pub(crate) mod u64_string {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(value)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(D::Error::custom)
+    }
+}
+
+pub(crate) mod i64_string {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(value: &i64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(value)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i64, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(D::Error::custom)
+    }
+}
+
+pub(crate) mod option_u64_string {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(value: &Option<u64>, serializer: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => serializer.collect_str(value),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<u64>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|value| value.parse().map_err(D::Error::custom))
+            .transpose()
+    }
+}
+
+pub(crate) mod base58 {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(value: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&bs58::encode(value).into_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        bs58::decode(String::deserialize(deserializer)?)
+            .into_vec()
+            .map_err(D::Error::custom)
+    }
+}
+
+/// The enums of the `pb` types, whose fields hold the `i32` value of the enum.
+pub(crate) trait ProtoEnum {
+    fn name(value: i32) -> Option<&'static str>;
+    fn value(name: &str) -> Option<i32>;
+}
+
+/// Serializes an enum field as the name of its value, or as the number itself for a value
+/// unknown to this version of the types, and reads either back.
+pub(crate) mod enumeration {
+    use super::*;
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum NameOrValue {
+        Name(String),
+        Value(i32),
+    }
+
+    pub fn serialize<E: ProtoEnum, S: Serializer>(
+        value: &i32,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match E::name(*value) {
+            Some(name) => serializer.serialize_str(name),
+            None => serializer.serialize_i32(*value),
+        }
+    }
+
+    pub fn deserialize<'de, E: ProtoEnum, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<i32, D::Error> {
+        match NameOrValue::deserialize(deserializer)? {
+            NameOrValue::Name(name) => E::value(&name)
+                .ok_or_else(|| D::Error::custom(format!("unknown enum value {name}"))),
+            NameOrValue::Value(value) => Ok(value),
+        }
+    }
+}
+
+/// Implements [ProtoEnum] for each enum, along with the module its fields are serialized
+/// with, as set by the `field_attribute`s of `buf.gen.yaml`.
+macro_rules! proto_enums {
+    ($($module:ident: $enum:ty),* $(,)?) => {
+        $(
+            impl ProtoEnum for $enum {
+                fn name(value: i32) -> Option<&'static str> {
+                    <$enum>::from_i32(value).map(|value| value.as_str_name())
+                }
+
+                fn value(name: &str) -> Option<i32> {
+                    <$enum>::from_str_name(name).map(|value| value as i32)
+                }
+            }
+
+            pub(crate) mod $module {
+                use super::*;
+
+                pub fn serialize<S: Serializer>(
+                    value: &i32,
+                    serializer: S,
+                ) -> Result<S::Ok, S::Error> {
+                    enumeration::serialize::<$enum, S>(value, serializer)
+                }
+
+                pub fn deserialize<'de, D: Deserializer<'de>>(
+                    deserializer: D,
+                ) -> Result<i32, D::Error> {
+                    enumeration::deserialize::<$enum, D>(deserializer)
+                }
+            }
+        )*
+    };
+}
+
+proto_enums!(
+    instruction_kind: InstructionKind,
+    initialize_account_version: InitializeAccountVersion,
+    initialize_mint_version: InitializeMintVersion,
+    initialize_multisig_version: InitializeMultisigVersion,
+    account_kind: AccountKind,
+    authority_type: AuthorityType,
+    wrapped_sol_kind: WrappedSolKind,
+);
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::pb::{
+        sf::solana::spl::token::v1::{
+            event::{self, InstructionKind},
+            set_authority::{
+                AccountKind, AuthorityType, SetAuthorityAccounts, SetAuthorityInstruction,
+            },
+            signer::Kind,
+            BalanceChange, Event, Events, MultiSignature, SetAuthority, Signer, Transaction,
+        },
+        sol::instructions::v1::Instruction,
+    };
+
+    fn events() -> Events {
+        Events {
+            data: vec![Event {
+                txn_id: "txn".to_string(),
+                block_height: u64::MAX,
+                block_timestamp: -1,
+                instruction_kind: InstructionKind::SetAuthority as i32,
+                raw_instruction: Some(Instruction {
+                    program_id: "program".to_string(),
+                    accounts: vec!["account".to_string()],
+                    data: vec![6, 1],
+                    tx_hash: "txn".to_string(),
+                }),
+                r#type: Some(event::Type::SetAuthority(SetAuthority {
+                    instruction: Some(SetAuthorityInstruction {
+                        authority_type: AuthorityType::AuthorityAccountOwner as i32,
+                        new_authority: None,
+                    }),
+                    accounts: Some(SetAuthorityAccounts {
+                        account: "account".to_string(),
+                        current_authority: "multisig".to_string(),
+                        account_kind: AccountKind::TokenAccount as i32,
+                        signer: Some(Signer {
+                            kind: Some(Kind::Multisig(MultiSignature {
+                                multisig_account: "multisig".to_string(),
+                                signers: vec!["signer".to_string()],
+                                threshold: Some(1),
+                                registered_signers: vec!["signer".to_string()],
+                                threshold_met: true,
+                            })),
+                        }),
+                    }),
+                })),
+                ..Default::default()
+            }],
+            balance_changes: vec![BalanceChange {
+                txn_id: "txn".to_string(),
+                pre_amount: 10,
+                ..Default::default()
+            }],
+            transactions: vec![Transaction {
+                txn_id: "txn".to_string(),
+                fee: 5000,
+                compute_units_consumed: Some(150),
+                ..Default::default()
+            }],
+        }
+    }
+
+    #[test]
+    fn test_json_shape() {
+        let json = serde_json::to_value(events()).unwrap();
+        let event = &json["data"][0];
+
+        assert_eq!(event["block_height"], json!("18446744073709551615"));
+        assert_eq!(event["block_timestamp"], json!("-1"));
+        assert_eq!(event["instruction_kind"], json!("SetAuthority"));
+        assert_eq!(event["raw_instruction"]["data"], json!("TW"));
+        assert_eq!(
+            event["type"]["set_authority"]["instruction"],
+            json!({ "authority_type": "AuthorityAccountOwner", "new_authority": null }),
+        );
+        assert_eq!(
+            event["type"]["set_authority"]["accounts"]["signer"]["kind"]["multisig"]["threshold"],
+            json!(1),
+        );
+        assert_eq!(json["balance_changes"][0]["pre_amount"], json!("10"));
+        assert_eq!(
+            json["transactions"][0]["compute_units_consumed"],
+            json!("150")
+        );
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let events = events();
+        let json = serde_json::to_string(&events).unwrap();
+        assert_eq!(serde_json::from_str::<Events>(&json).unwrap(), events);
+    }
+
+    #[test]
+    fn test_json_unknown_enum_value() {
+        let event = Event {
+            instruction_kind: 99,
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_value(&event).unwrap()["instruction_kind"],
+            json!(99)
+        );
+
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(serde_json::from_value::<Event>(json).unwrap(), event);
+
+        let mut json = serde_json::to_value(Event::default()).unwrap();
+        json["instruction_kind"] = json!(13);
+        assert_eq!(
+            serde_json::from_value::<Event>(json.clone())
+                .unwrap()
+                .instruction_kind,
+            InstructionKind::TransferChecked as i32
+        );
+
+        json["instruction_kind"] = json!("NotAnInstruction");
+        assert!(serde_json::from_value::<Event>(json).is_err());
+    }
+
+    #[test]
+    fn test_json_matches_schema() {
+        let schema: serde_json::Value =
+            serde_json::from_str(include_str!("../events.schema.json")).unwrap();
+        let schema = jsonschema::JSONSchema::compile(&schema).unwrap();
+
+        assert!(schema.is_valid(&serde_json::to_value(events()).unwrap()));
+
+        let mut json = serde_json::to_value(events()).unwrap();
+        json["data"][0]["block_height"] = json!(1);
+        assert!(!schema.is_valid(&json));
+    }
+}
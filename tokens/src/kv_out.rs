@@ -0,0 +1,94 @@
+use std::collections::HashSet;
+
+use crate::pb::sf::solana::spl::token::v1::{
+    event::Type, set_authority::AuthorityType, CloseAccount, Events, InitializeMint, SetAuthority,
+};
+use prost::Message;
+use substreams::{
+    skip_empty_output,
+    store::{DeltaBigInt, Deltas},
+};
+use substreams_sink_kv::pb::sf::substreams::sink::kv::v1::KvOperations;
+
+/// Emits the key-value operations for point lookups:
+///
+/// - `balance:<account>`: the latest `BalanceChange` of the token account
+/// - `owner:<owner>:<account>`: the mint of each token account of an owner, scan the
+///   `owner:<owner>:` prefix to list the accounts of a wallet
+/// - `mint:<mint>`: the `InitializeMint` event the mint was created with
+/// - `supply:<mint>`: the current supply of the mint, as a decimal string
+///
+/// The `balance` and `owner` keys of token accounts closed in the block are deleted, and so is
+/// the `owner` key of the previous owner of a token account whose owner changed.
+#[substreams::handlers::map]
+fn kv_out(
+    events: Events,
+    mint_supply_deltas: Deltas<DeltaBigInt>,
+) -> Result<KvOperations, substreams::errors::Error> {
+    skip_empty_output();
+
+    let mut operations = KvOperations::default();
+    let mut ordinal = 0;
+    let mut next_ordinal = || {
+        ordinal += 1;
+        ordinal
+    };
+
+    let mut closed_accounts = HashSet::new();
+    for event in &events.data {
+        let Some(event_type) = event.r#type.as_ref() else {
+            continue;
+        };
+
+        match event_type {
+            Type::InitializeMint(initialize_mint @ InitializeMint { accounts, .. }) => {
+                operations.push_new(
+                    format!("mint:{}", accounts.as_ref().unwrap().mint),
+                    initialize_mint.encode_to_vec(),
+                    next_ordinal(),
+                );
+            }
+            Type::CloseAccount(CloseAccount { accounts, .. }) => {
+                closed_accounts.insert(accounts.as_ref().unwrap().account.as_str());
+            }
+            // Deleted before the keys of the new owners are set below, which restores the key
+            // of an account moved back to its owner within the block
+            Type::SetAuthority(SetAuthority {
+                instruction,
+                accounts,
+            }) if instruction.as_ref().unwrap().authority_type()
+                == AuthorityType::AuthorityAccountOwner =>
+            {
+                let accounts = accounts.as_ref().unwrap();
+                operations.push_delete(
+                    format!("owner:{}:{}", accounts.current_authority, accounts.account),
+                    next_ordinal(),
+                );
+            }
+            _ => {}
+        }
+    }
+
+    for change in &events.balance_changes {
+        let balance_key = format!("balance:{}", change.account);
+        let owner_key = format!("owner:{}:{}", change.owner, change.account);
+
+        if closed_accounts.contains(change.account.as_str()) {
+            operations.push_delete(balance_key, next_ordinal());
+            operations.push_delete(owner_key, next_ordinal());
+        } else {
+            operations.push_new(balance_key, change.encode_to_vec(), next_ordinal());
+            operations.push_new(owner_key, change.mint.as_bytes(), next_ordinal());
+        }
+    }
+
+    for delta in mint_supply_deltas.deltas {
+        operations.push_new(
+            format!("supply:{}", delta.key),
+            delta.new_value.to_string().as_bytes(),
+            next_ordinal(),
+        );
+    }
+
+    Ok(operations)
+}
@@ -1,4 +1,22 @@
+mod account_state;
+mod associated_account;
+mod authority_changes;
+mod block_meta;
 mod db_out;
+mod delegations;
+mod freeze_alerts;
+mod graph_out;
 mod instructions;
+#[cfg(feature = "serde")]
+mod json;
+mod kv_out;
 mod map_block;
-mod pb;
+mod map_transactions;
+mod mint_stats;
+mod multisig;
+pub mod pb;
+mod store_mint_supply;
+mod wrapped_sol;
+
+pub use instructions::{decode_instruction, TransactionSigners};
+pub use map_block::{to_events, Filter};
@@ -1,72 +1,555 @@
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::anyhow;
+
+use crate::associated_account::ASSOCIATED_TOKEN_ACCOUNT_PROGRAM_ID;
+use crate::block_meta::to_block_meta;
+use crate::instructions::{decode_instruction, TransactionSigners};
+use crate::multisig::resolve_multisig;
 use crate::pb::{
     sf::solana::spl::token::v1::{
-        event::Type, Approve, Burn, CloseAccount, Event, Events, FreezeAccount, InitializeAccount,
-        InitializeImmutableOwner, InitializeMint, InitializeMultisig, MintTo, Revoke, SetAuthority,
-        SyncNative, ThawAccount, Transfer,
+        event::{InstructionKind, Type},
+        set_authority::AccountKind,
+        Approve, AssociatedAccountCreated, AssociatedAccountNestedRecovered, BalanceChange,
+        BlockMeta, Burn, CloseAccount, Event, Events, FreezeAccount, InitializeAccount,
+        InitializeImmutableOwner, InitializeMint, InitializeMultisig, MintTo, Multisig, Revoke,
+        SetAuthority, SyncNative, ThawAccount, Transaction, Transfer,
     },
-    sol::transactions::v1::Transactions,
+    sol::{instructions::v1::Instruction, transactions::v1::Transactions},
+};
+use crate::wrapped_sol::rent_exempt_reserve;
+use spl_token::solana_program::{pubkey, pubkey::Pubkey};
+use substreams::{
+    skip_empty_output,
+    store::{StoreGet, StoreGetProto},
+};
+use substreams_solana::{
+    block_view::InstructionView,
+    pb::sf::solana::r#type::v1::{Block, ConfirmedTransaction, TokenBalance},
 };
-use substreams::{pb::substreams::Clock, skip_empty_output};
-use substreams_solana::pb::sf::solana::r#type::v1::ConfirmedTransaction;
 
+const MEMO_V1_PROGRAM_ID: Pubkey = pubkey!("Memo1UhkJRfHyvLMcVucJwxXeuD728EqVDDwQDxFMNo");
+const MEMO_V2_PROGRAM_ID: Pubkey = pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+
+/// The events kept by [to_events], parsed from the `map_block` params.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Filter {
+    pub token_contract: String,
+    /// Whether each event carries its raw instruction, see [Event::raw_instruction].
+    pub raw_instructions: bool,
+}
+
+impl Filter {
+    /// Parses params of the form `token_contract:<address>`, optionally followed by
+    /// `,raw_instructions:true` to attach the raw instruction to each event.
+    pub fn from_params(params: &str) -> Result<Self, substreams::errors::Error> {
+        let mut filter = Filter::default();
+        for param in params.split(',') {
+            match param.trim().split_once(':') {
+                Some(("token_contract", token_contract)) if !token_contract.is_empty() => {
+                    filter.token_contract = token_contract.to_string();
+                }
+                Some(("raw_instructions", raw_instructions)) => {
+                    filter.raw_instructions = raw_instructions.parse().map_err(|_| {
+                        anyhow!("Invalid raw_instructions {raw_instructions:?}, must be a boolean")
+                    })?;
+                }
+                _ => return Err(anyhow!("Invalid param {param:?} in {params:?}")),
+            }
+        }
+
+        if filter.token_contract.is_empty() {
+            return Err(anyhow!(
+                "Invalid params {params:?}, must be of the form token_contract:<address>"
+            ));
+        }
+
+        Ok(filter)
+    }
+}
+
+/// Reads the full block rather than its transactions involving the SPL Token program, so that
+/// the index of each transaction is its index in the block.
 #[substreams::handlers::map]
 fn map_block(
     params: String,
-    clock: Clock,
-    trxs: Transactions,
+    block: Block,
+    multisigs: StoreGetProto<Multisig>,
 ) -> Result<Events, substreams::errors::Error> {
     skip_empty_output();
-    let colon_index = params
-        .find(":")
-        .expect("Invalid params, must be of the form token_contract:<address>");
-    let token_contract = &params[colon_index + 1..];
-
-    let block_height = clock.number;
-    let block_timestamp = clock
-        .timestamp
-        .as_ref()
-        .map(|t| t.seconds)
-        .unwrap_or_default();
+    let filter = Filter::from_params(&params)?;
+
+    to_events(
+        &filter,
+        &to_block_meta(&block),
+        Transactions {
+            transactions: block.transactions,
+        },
+        |account| multisigs.get_last(account),
+    )
+}
+
+/// Decodes the token events of the transactions of `block` matching `filter`, along with the
+/// balance changes and the details of the transactions having emitted them. `trxs` are all the
+/// transactions of the block, each one's position being its index in the block. The multisig
+/// accounts of the signers are looked up with `multisig` to resolve their signer sets. Fails on
+/// a SPL Token instruction that can't be unpacked.
+pub fn to_events(
+    filter: &Filter,
+    block: &BlockMeta,
+    trxs: Transactions,
+    multisig: impl Fn(&str) -> Option<Multisig>,
+) -> Result<Events, substreams::errors::Error> {
+    let token_contract = filter.token_contract.as_str();
 
     let mut data: Vec<Event> = Vec::new();
-    for confirmed_txn in trxs.transactions {
+    let mut balance_changes: Vec<BalanceChange> = Vec::new();
+    let mut transactions: Vec<Transaction> = Vec::new();
+    for (trx_index, confirmed_txn) in trxs.transactions.into_iter().enumerate() {
         if confirmed_txn.meta().is_none() {
             continue;
         }
 
         let tx_id = confirmed_txn.id();
+        let event_count = data.len();
+        let mut call_stack = CallStack::default();
+        let mut memos: Vec<String> = Vec::new();
+        let mut unmodeled: Vec<Event> = Vec::new();
+        let mut token_accounts = TokenAccounts::new(&confirmed_txn);
+        let signers = TransactionSigners::new(&confirmed_txn);
         for (i, instruction) in confirmed_txn.walk_instructions().enumerate() {
-            if instruction.program_id() != spl_token::ID {
+            let position = call_stack.push(&instruction);
+            if instruction.program_id() == MEMO_V1_PROGRAM_ID
+                || instruction.program_id() == MEMO_V2_PROGRAM_ID
+            {
+                memos.push(String::from_utf8_lossy(instruction.data()).into_owned());
                 continue;
             }
 
-            let token_instruction =
-                spl_token::instruction::TokenInstruction::unpack(instruction.data())?;
-
-            let event = match Type::try_from((token_instruction, &instruction)) {
-                Ok(event_type) => Event {
-                    txn_id: tx_id.clone(),
-                    block_height,
-                    block_timestamp,
-                    block_hash: clock.id.clone(),
-                    instruction_index: i as u32,
-                    r#type: Some(event_type),
-                },
-                Err(_) => continue,
+            // Instructions that aren't modeled are only kept along with their raw instruction
+            let event_type = match decode_instruction(&instruction, &signers)? {
+                Some(event_type) => Some(event_type),
+                None if filter.raw_instructions => None,
+                None => continue,
             };
+            if let Some(event_type) = &event_type {
+                token_accounts.apply(event_type);
+            }
 
-            if event
-                .r#type
+            let is_associated_account = position
+                .parent_program_id
                 .as_ref()
-                .unwrap()
-                .is_for_token_contract(&confirmed_txn, &token_contract)
-            {
+                .is_some_and(|id| id.as_slice() == ASSOCIATED_TOKEN_ACCOUNT_PROGRAM_ID.as_ref());
+
+            let mut event = Event {
+                txn_id: tx_id.clone(),
+                slot: block.slot,
+                block_height: block.block_height,
+                block_timestamp: block.block_timestamp,
+                block_hash: block.block_hash.clone(),
+                parent_slot: block.parent_slot,
+                previous_block_hash: block.previous_block_hash.clone(),
+                instruction_index: i as u32,
+                outer_instruction_index: position.outer_instruction_index,
+                inner_instruction_index: position.inner_instruction_index,
+                stack_height: position.stack_height,
+                parent_program_id: position
+                    .parent_program_id
+                    .map(|id| bs58::encode(id).into_string()),
+                instruction_kind: InstructionKind::from_instruction(&instruction) as i32,
+                raw_instruction: filter
+                    .raw_instructions
+                    .then(|| to_raw_instruction(&instruction, &tx_id)),
+                r#type: event_type,
+            };
+
+            match event.r#type.as_mut() {
+                Some(Type::InitializeAccount(initialize_account)) => {
+                    initialize_account.is_associated_account = is_associated_account;
+                }
+                Some(Type::CloseAccount(close_account)) => {
+                    set_closed_account_balances(&confirmed_txn, &mut token_accounts, close_account);
+                }
+                _ => {}
+            }
+
+            let Some(event_type) = event.r#type.as_mut() else {
+                unmodeled.push(event);
+                continue;
+            };
+            if event_type.is_for_token_contract(&confirmed_txn, token_contract) {
+                if let Some(signer) = event_type.signer_mut() {
+                    resolve_multisig(signer, &multisig);
+                }
                 data.push(event);
             }
         }
+
+        if !memos.is_empty() {
+            let memo = memos.join("; ");
+            for event in &mut data[event_count..] {
+                if let Some(Type::Transfer(transfer)) = event.r#type.as_mut() {
+                    transfer.memo = Some(memo.clone());
+                }
+            }
+        }
+
+        if data.len() > event_count {
+            data.extend(unmodeled);
+            data[event_count..].sort_by_key(|event| event.instruction_index);
+            balance_changes.extend(to_balance_changes(&confirmed_txn, &tx_id, token_contract));
+            transactions.push(to_transaction(
+                &confirmed_txn,
+                tx_id,
+                trx_index as u32,
+                signers,
+            ));
+        }
+    }
+
+    Ok(Events {
+        data,
+        balance_changes,
+        transactions,
+    })
+}
+
+fn to_raw_instruction(instruction: &InstructionView<'_>, tx_id: &str) -> Instruction {
+    Instruction {
+        program_id: instruction.program_id().to_string(),
+        accounts: instruction
+            .accounts()
+            .iter()
+            .map(|account| account.to_string())
+            .collect(),
+        data: instruction.data().clone(),
+        tx_hash: tx_id.to_string(),
+    }
+}
+
+fn to_transaction(
+    trx: &ConfirmedTransaction,
+    tx_id: String,
+    index: u32,
+    signers: TransactionSigners,
+) -> Transaction {
+    let meta = trx.meta.as_ref().unwrap();
+    let message = trx.transaction.as_ref().unwrap().message.as_ref().unwrap();
+    let signers = signers.into_vec();
+
+    Transaction {
+        txn_id: tx_id,
+        index,
+        fee_payer: signers.first().cloned().unwrap_or_default(),
+        signers,
+        fee: meta.fee,
+        compute_units_consumed: meta.compute_units_consumed,
+        recent_blockhash: bs58::encode(&message.recent_blockhash).into_string(),
+        success: meta.err.is_none(),
+    }
+}
+
+/// The position of an instruction within its transaction, see [CallStack::push].
+struct InstructionPosition {
+    outer_instruction_index: u32,
+    inner_instruction_index: Option<u32>,
+    stack_height: u32,
+    parent_program_id: Option<Vec<u8>>,
+}
+
+/// Tracks the programs being invoked while walking the instructions of a transaction, in
+/// execution order, to find out the position and the invoking program of each instruction.
+#[derive(Default)]
+struct CallStack {
+    outer_instruction_index: Option<u32>,
+    inner_instruction_index: Option<u32>,
+    programs: Vec<Vec<u8>>,
+}
+
+impl CallStack {
+    fn push(&mut self, instruction: &InstructionView) -> InstructionPosition {
+        let program_id = instruction.program_id().0.clone();
+
+        if instruction.is_root() {
+            self.outer_instruction_index = Some(self.outer_instruction_index.map_or(0, |i| i + 1));
+            self.inner_instruction_index = None;
+            self.programs = vec![program_id];
+
+            return InstructionPosition {
+                outer_instruction_index: self.outer_instruction_index.unwrap(),
+                inner_instruction_index: None,
+                stack_height: 1,
+                parent_program_id: None,
+            };
+        }
+
+        self.inner_instruction_index = Some(self.inner_instruction_index.map_or(0, |i| i + 1));
+
+        // Transactions recorded before the stack height was tracked report 0, all we know
+        // then is that the instruction was invoked under the top-level instruction.
+        let stack_height = instruction.stack_height();
+        let parent_program_id = if stack_height < 2 {
+            self.programs.first().cloned()
+        } else {
+            self.programs.truncate(stack_height as usize - 1);
+            let parent = self.programs.last().cloned();
+            self.programs.push(program_id);
+            parent
+        };
+
+        InstructionPosition {
+            outer_instruction_index: self.outer_instruction_index.unwrap_or_default(),
+            inner_instruction_index: self.inner_instruction_index,
+            stack_height,
+            parent_program_id,
+        }
+    }
+}
+
+/// Pairs the pre and post token balances of the transaction for the accounts holding
+/// `mint`. Accounts closed by the transaction have no post balance and are reported with
+/// a `post_amount` of zero.
+fn to_balance_changes(trx: &ConfirmedTransaction, tx_id: &str, mint: &str) -> Vec<BalanceChange> {
+    let meta = trx.meta.as_ref().unwrap();
+    let accounts = resolved_accounts(trx);
+
+    let mut changes: BTreeMap<u32, BalanceChange> = BTreeMap::new();
+    for (balance, is_post) in meta
+        .pre_token_balances
+        .iter()
+        .map(|b| (b, false))
+        .chain(meta.post_token_balances.iter().map(|b| (b, true)))
+    {
+        if balance.mint != mint {
+            continue;
+        }
+
+        let change = changes
+            .entry(balance.account_index)
+            .or_insert_with(|| BalanceChange {
+                txn_id: tx_id.to_string(),
+                account: accounts
+                    .get(balance.account_index as usize)
+                    .cloned()
+                    .unwrap_or_default(),
+                mint: balance.mint.clone(),
+                owner: balance.owner.clone(),
+                decimals: token_balance_decimals(balance),
+                ..Default::default()
+            });
+
+        if is_post {
+            change.owner = balance.owner.clone();
+            change.post_amount = token_balance_amount(balance);
+        } else {
+            change.pre_amount = token_balance_amount(balance);
+        }
+    }
+
+    changes.into_values().collect()
+}
+
+pub(crate) fn token_balance_amount(balance: &TokenBalance) -> u64 {
+    balance
+        .ui_token_amount
+        .as_ref()
+        .and_then(|amount| amount.amount.parse().ok())
+        .unwrap_or_default()
+}
+
+fn token_balance_decimals(balance: &TokenBalance) -> u32 {
+    balance
+        .ui_token_amount
+        .as_ref()
+        .map(|amount| amount.decimals)
+        .unwrap_or_default()
+}
+
+/// Sets the mint, token balance and reclaimed lamports of the closed account. Its mint and
+/// token balance are the ones it had when it was closed, followed by `token_accounts`.
+///
+/// The lamports of an account that existed before the transaction are its balance when the
+/// transaction started less its balance when it ended. An account created by the transaction
+/// has no balance before it, its lamports are then the credit of the destination over the
+/// transaction, the fee excluded when the destination paid it, and at least the rent-exempt
+/// reserve and token balance of the account when the destination also funded it.
+fn set_closed_account_balances(
+    trx: &ConfirmedTransaction,
+    token_accounts: &mut TokenAccounts,
+    close_account: &mut CloseAccount,
+) {
+    let meta = trx.meta.as_ref().unwrap();
+    let accounts = close_account.accounts.as_ref().unwrap();
+    if let Some(closed) = token_accounts.close(&accounts.account) {
+        close_account.mint = closed.mint;
+        close_account.amount = closed.amount;
+    }
+
+    let keys = resolved_accounts(trx);
+    let lamports = |account: &str| {
+        let index = keys.iter().position(|key| key == account)?;
+        let pre = meta.pre_balances.get(index).copied().unwrap_or_default();
+        let post = meta.post_balances.get(index).copied().unwrap_or_default();
+        Some((index, pre, post))
+    };
+
+    close_account.reclaimed_lamports = match lamports(&accounts.account) {
+        Some((_, pre, post)) if pre > 0 => pre.saturating_sub(post),
+        _ => {
+            let credit = match lamports(&accounts.destination) {
+                // The fee payer is the first account
+                Some((0, pre, post)) => post.saturating_add(meta.fee).saturating_sub(pre),
+                Some((_, pre, post)) => post.saturating_sub(pre),
+                None => 0,
+            };
+            credit.max(rent_exempt_reserve().saturating_add(close_account.amount))
+        }
+    };
+}
+
+/// The mint and token balance of the token accounts of a transaction, followed instruction by
+/// instruction from its pre token balances, so that the balance of an account is known when it
+/// is closed, even when it was created by the same transaction. `SyncNative` isn't followed,
+/// the lamports deposited into native accounts being unknown.
+struct TokenAccounts {
+    accounts: HashMap<String, TokenAccount>,
+}
+
+struct TokenAccount {
+    mint: String,
+    amount: u64,
+}
+
+impl TokenAccounts {
+    fn new(trx: &ConfirmedTransaction) -> Self {
+        let keys = resolved_accounts(trx);
+        let accounts = trx
+            .meta
+            .as_ref()
+            .unwrap()
+            .pre_token_balances
+            .iter()
+            .filter_map(|balance| {
+                let key = keys.get(balance.account_index as usize)?;
+                let account = TokenAccount {
+                    mint: balance.mint.clone(),
+                    amount: token_balance_amount(balance),
+                };
+                Some((key.clone(), account))
+            })
+            .collect();
+
+        Self { accounts }
+    }
+
+    /// Applies the instruction to the balances of the accounts it changes.
+    fn apply(&mut self, event_type: &Type) {
+        match event_type {
+            Type::InitializeAccount(InitializeAccount { accounts, .. }) => {
+                let accounts = accounts.as_ref().unwrap();
+                let account = TokenAccount {
+                    mint: accounts.mint.clone(),
+                    amount: 0,
+                };
+                self.accounts.insert(accounts.account.clone(), account);
+            }
+            Type::Transfer(Transfer {
+                instruction,
+                accounts,
+                ..
+            }) => {
+                let accounts = accounts.as_ref().unwrap();
+                let amount = instruction.as_ref().unwrap().amount;
+                let mint = accounts.token_mint.clone().or_else(|| {
+                    self.accounts
+                        .get(&accounts.source)
+                        .map(|account| account.mint.clone())
+                });
+
+                self.debit(&accounts.source, amount);
+                self.credit(&accounts.destination, mint, amount);
+            }
+            Type::MintTo(MintTo {
+                instruction,
+                accounts,
+            }) => {
+                let accounts = accounts.as_ref().unwrap();
+                let amount = instruction.as_ref().unwrap().amount;
+                self.credit(&accounts.destination, Some(accounts.mint.clone()), amount);
+            }
+            Type::Burn(Burn {
+                instruction,
+                accounts,
+            }) => {
+                let amount = instruction.as_ref().unwrap().amount;
+                self.debit(&accounts.as_ref().unwrap().source, amount);
+            }
+            _ => {}
+        }
+    }
+
+    /// Removes the closed account, returning its mint and token balance when it was closed.
+    fn close(&mut self, account: &str) -> Option<TokenAccount> {
+        self.accounts.remove(account)
+    }
+
+    fn credit(&mut self, account: &str, mint: Option<String>, amount: u64) {
+        match (self.accounts.get_mut(account), mint) {
+            (Some(account), _) => account.amount = account.amount.saturating_add(amount),
+            (None, Some(mint)) => {
+                self.accounts
+                    .insert(account.to_string(), TokenAccount { mint, amount });
+            }
+            (None, None) => {}
+        }
+    }
+
+    fn debit(&mut self, account: &str, amount: u64) {
+        if let Some(account) = self.accounts.get_mut(account) {
+            account.amount = account.amount.saturating_sub(amount);
+        }
     }
+}
+
+/// Returns the mint of a token account of the transaction, found in its token balances.
+fn account_mint<'a>(trx: &'a ConfirmedTransaction, account: &str) -> Option<&'a str> {
+    let meta = trx.meta.as_ref().unwrap();
+    let account_index = resolved_accounts(trx)
+        .iter()
+        .position(|key| key == account)?;
+
+    meta.pre_token_balances
+        .iter()
+        .chain(meta.post_token_balances.iter())
+        .find(|balance| balance.account_index as usize == account_index)
+        .map(|balance| balance.mint.as_str())
+}
+
+/// Returns the base58 encoded accounts of the transaction in the order used by account
+/// indexes, that is the static account keys followed by the writable and then readonly
+/// addresses loaded from address lookup tables.
+pub(crate) fn resolved_accounts(trx: &ConfirmedTransaction) -> Vec<String> {
+    let message = trx
+        .transaction
+        .as_ref()
+        .and_then(|transaction| transaction.message.as_ref());
+    let meta = trx.meta.as_ref();
 
-    Ok(Events { data })
+    message
+        .map(|message| message.account_keys.iter())
+        .into_iter()
+        .flatten()
+        .chain(
+            meta.map(|meta| {
+                meta.loaded_writable_addresses
+                    .iter()
+                    .chain(meta.loaded_readonly_addresses.iter())
+            })
+            .into_iter()
+            .flatten(),
+        )
+        .map(|key| bs58::encode(key).into_string())
+        .collect()
 }
 
 impl Type {
@@ -102,36 +585,484 @@ impl Type {
                 // FIXME: How to filter that out?
                 false
             }
-            Type::Approve(Approve { accounts: _, .. }) => {
-                // FIXME: How to filter that out?
-                false
+            Type::Approve(Approve { accounts, .. }) => {
+                let accounts = accounts.as_ref().unwrap();
+                match &accounts.token_mint {
+                    Some(token_mint) => token_mint == contract,
+                    None => account_mint(trx, &accounts.source) == Some(contract),
+                }
             }
             Type::MintTo(MintTo { accounts, .. }) => accounts.as_ref().unwrap().mint == contract,
-            Type::Revoke(Revoke { accounts: _, .. }) => {
-                // FIXME: How to filter that out?
-                false
+            Type::Revoke(Revoke { accounts, .. }) => {
+                account_mint(trx, &accounts.as_ref().unwrap().source) == Some(contract)
             }
-            Type::SetAuthority(SetAuthority { accounts: _, .. }) => {
-                // FIXME: How to filter that out?
-                false
+            Type::SetAuthority(SetAuthority { accounts, .. }) => {
+                let accounts = accounts.as_ref().unwrap();
+                match accounts.account_kind() {
+                    AccountKind::Mint => accounts.account == contract,
+                    _ => account_mint(trx, &accounts.account) == Some(contract),
+                }
             }
             Type::Burn(Burn { accounts, .. }) => accounts.as_ref().unwrap().mint == contract,
-            Type::CloseAccount(CloseAccount { accounts: _, .. }) => {
-                // FIXME: How to filter that out?
-                false
-            }
-            Type::FreezeAccount(FreezeAccount { accounts: _, .. }) => {
-                // FIXME: How to filter that out?
-                false
+            Type::CloseAccount(CloseAccount { mint, .. }) => mint == contract,
+            Type::FreezeAccount(FreezeAccount { accounts, .. }) => {
+                accounts.as_ref().unwrap().mint == contract
             }
-            Type::ThawAccount(ThawAccount { accounts: _, .. }) => {
-                // FIXME: How to filter that out?
-                false
+            Type::ThawAccount(ThawAccount { accounts, .. }) => {
+                accounts.as_ref().unwrap().mint == contract
             }
             Type::SyncNative(SyncNative { accounts: _, .. }) => {
                 // FIXME: How to filter that out?
                 false
             }
+            Type::AssociatedAccountCreated(AssociatedAccountCreated { accounts, .. }) => {
+                accounts.as_ref().unwrap().mint == contract
+            }
+            Type::AssociatedAccountNestedRecovered(AssociatedAccountNestedRecovered {
+                accounts,
+                ..
+            }) => {
+                let accounts = accounts.as_ref().unwrap();
+                accounts.nested_mint == contract || accounts.owner_mint == contract
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pb::sf::solana::spl::token::v1::signer::Kind;
+    use spl_token::instruction;
+    use substreams_solana::pb::sf::solana::r#type::v1::{
+        CompiledInstruction, ConfirmedTransaction, Message, MessageHeader,
+        Transaction as SolanaTransaction, TransactionStatusMeta, UiTokenAmount,
+    };
+
+    const TOKEN_PROGRAM_INDEX: u32 = 4;
+
+    /// The accounts of the test transactions: the owner signing them, two token accounts, the
+    /// mint and the token program.
+    struct Keys {
+        owner: Pubkey,
+        source: Pubkey,
+        destination: Pubkey,
+        mint: Pubkey,
+    }
+
+    impl Keys {
+        fn new() -> Self {
+            Keys {
+                owner: Pubkey::new_unique(),
+                source: Pubkey::new_unique(),
+                destination: Pubkey::new_unique(),
+                mint: Pubkey::new_unique(),
+            }
+        }
+
+        fn account_keys(&self) -> Vec<Vec<u8>> {
+            [
+                self.owner,
+                self.source,
+                self.destination,
+                self.mint,
+                spl_token::ID,
+            ]
+            .iter()
+            .map(|key| key.to_bytes().to_vec())
+            .collect()
+        }
+    }
+
+    /// A transfer of 10 tokens from `source` to `destination`, indexes referring to
+    /// [Keys::account_keys].
+    fn transfer_checked(keys: &Keys) -> CompiledInstruction {
+        let data = instruction::transfer_checked(
+            &spl_token::ID,
+            &keys.source,
+            &keys.mint,
+            &keys.destination,
+            &keys.owner,
+            &[],
+            10,
+            6,
+        )
+        .unwrap()
+        .data;
+
+        CompiledInstruction {
+            program_id_index: TOKEN_PROGRAM_INDEX,
+            accounts: vec![1, 3, 2, 0],
+            data,
+        }
+    }
+
+    fn transaction(
+        signature: u8,
+        keys: &Keys,
+        instructions: Vec<CompiledInstruction>,
+    ) -> ConfirmedTransaction {
+        ConfirmedTransaction {
+            transaction: Some(SolanaTransaction {
+                signatures: vec![vec![signature; 64]],
+                message: Some(Message {
+                    header: Some(MessageHeader {
+                        num_required_signatures: 1,
+                        ..Default::default()
+                    }),
+                    account_keys: keys.account_keys(),
+                    instructions,
+                    ..Default::default()
+                }),
+            }),
+            meta: Some(TransactionStatusMeta::default()),
+        }
+    }
+
+    fn filter(keys: &Keys) -> Filter {
+        Filter {
+            token_contract: keys.mint.to_string(),
+            raw_instructions: false,
+        }
+    }
+
+    fn block() -> BlockMeta {
+        BlockMeta {
+            slot: 42,
+            block_height: 40,
+            parent_slot: 41,
+            block_hash: "block".to_string(),
+            previous_block_hash: "previous".to_string(),
+            block_timestamp: 1_700_000_000,
+        }
+    }
+
+    fn no_multisig(_: &str) -> Option<Multisig> {
+        None
+    }
+
+    #[test]
+    fn test_filter_from_params() {
+        assert_eq!(
+            Filter::from_params("token_contract:EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v")
+                .unwrap(),
+            Filter {
+                token_contract: "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string(),
+                raw_instructions: false,
+            }
+        );
+        assert_eq!(
+            Filter::from_params(
+                "token_contract:EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v,raw_instructions:true"
+            )
+            .unwrap(),
+            Filter {
+                token_contract: "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string(),
+                raw_instructions: true,
+            }
+        );
+        assert!(Filter::from_params("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v").is_err());
+        assert!(Filter::from_params("token_contract:").is_err());
+        assert!(Filter::from_params("raw_instructions:true").is_err());
+        assert!(Filter::from_params(
+            "token_contract:EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v,raw_instructions:yes"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_to_events_filters_token_contract() {
+        let keys = Keys::new();
+        let other = Keys::new();
+        let trxs = Transactions {
+            transactions: vec![
+                transaction(1, &keys, vec![transfer_checked(&keys)]),
+                transaction(2, &other, vec![transfer_checked(&other)]),
+            ],
+        };
+
+        let events = to_events(&filter(&keys), &block(), trxs, no_multisig).unwrap();
+
+        assert_eq!(events.data.len(), 1);
+        let event = &events.data[0];
+        assert_eq!(event.txn_id, bs58::encode([1u8; 64]).into_string());
+        assert_eq!(event.slot, 42);
+        assert_eq!(event.block_height, 40);
+        assert_eq!(event.parent_slot, 41);
+        assert_eq!(event.block_hash, "block");
+        assert_eq!(event.previous_block_hash, "previous");
+        assert_eq!(event.block_timestamp, 1_700_000_000);
+        assert!(matches!(event.r#type, Some(Type::Transfer(_))));
+        assert_eq!(event.instruction_kind(), InstructionKind::TransferChecked);
+
+        assert_eq!(events.transactions.len(), 1);
+        assert_eq!(events.transactions[0].index, 0);
+        assert_eq!(events.transactions[0].fee_payer, keys.owner.to_string());
+        assert!(events.transactions[0].success);
+    }
+
+    #[test]
+    fn test_to_events_raw_instructions() {
+        let keys = Keys::new();
+        let transfer = transfer_checked(&keys);
+        let trxs = Transactions {
+            transactions: vec![transaction(1, &keys, vec![transfer.clone()])],
+        };
+
+        let events = to_events(&filter(&keys), &block(), trxs.clone(), no_multisig).unwrap();
+        assert_eq!(events.data[0].raw_instruction, None);
+
+        let filter = Filter {
+            raw_instructions: true,
+            ..filter(&keys)
+        };
+        let events = to_events(&filter, &block(), trxs, no_multisig).unwrap();
+        assert_eq!(
+            events.data[0].raw_instruction,
+            Some(Instruction {
+                program_id: spl_token::ID.to_string(),
+                accounts: vec![
+                    keys.source.to_string(),
+                    keys.mint.to_string(),
+                    keys.destination.to_string(),
+                    keys.owner.to_string(),
+                ],
+                data: transfer.data,
+                tx_hash: bs58::encode([1u8; 64]).into_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_to_events_raw_instructions_unmodeled() {
+        let keys = Keys::new();
+        // Any program but the token programs, here the mint's key
+        let unmodeled = CompiledInstruction {
+            program_id_index: 3,
+            accounts: vec![0, 1],
+            data: vec![7],
+        };
+        let trxs = Transactions {
+            transactions: vec![
+                transaction(1, &keys, vec![unmodeled.clone(), transfer_checked(&keys)]),
+                transaction(2, &keys, vec![unmodeled]),
+            ],
+        };
+
+        let events = to_events(&filter(&keys), &block(), trxs.clone(), no_multisig).unwrap();
+        assert_eq!(events.data.len(), 1);
+
+        let filter = Filter {
+            raw_instructions: true,
+            ..filter(&keys)
+        };
+        let events = to_events(&filter, &block(), trxs, no_multisig).unwrap();
+
+        assert_eq!(events.data.len(), 2);
+        let event = &events.data[0];
+        assert_eq!(event.instruction_index, 0);
+        assert_eq!(event.instruction_kind, InstructionKind::Unspecified as i32);
+        assert_eq!(event.r#type, None);
+        assert_eq!(
+            event.raw_instruction.as_ref().unwrap().program_id,
+            keys.mint.to_string()
+        );
+        assert_eq!(events.data[1].instruction_index, 1);
+        assert!(matches!(events.data[1].r#type, Some(Type::Transfer(_))));
+        assert_eq!(events.transactions.len(), 1);
+    }
+
+    #[test]
+    fn test_to_events_skips_transactions_without_meta() {
+        let keys = Keys::new();
+        let mut trx = transaction(1, &keys, vec![transfer_checked(&keys)]);
+        trx.meta = None;
+
+        let events = to_events(
+            &filter(&keys),
+            &block(),
+            Transactions {
+                transactions: vec![trx],
+            },
+            no_multisig,
+        )
+        .unwrap();
+
+        assert_eq!(events, Events::default());
+    }
+
+    #[test]
+    fn test_to_events_skips_unsupported_instructions() {
+        let keys = Keys::new();
+        let get_account_data_size = CompiledInstruction {
+            program_id_index: TOKEN_PROGRAM_INDEX,
+            accounts: vec![3],
+            data: vec![21],
+        };
+        let trxs = Transactions {
+            transactions: vec![transaction(
+                1,
+                &keys,
+                vec![get_account_data_size, transfer_checked(&keys)],
+            )],
+        };
+
+        let events = to_events(&filter(&keys), &block(), trxs, no_multisig).unwrap();
+
+        assert_eq!(events.data.len(), 1);
+        assert_eq!(events.data[0].instruction_index, 1);
+    }
+
+    #[test]
+    fn test_to_events_fails_on_undecodable_instructions() {
+        let keys = Keys::new();
+        let undecodable = CompiledInstruction {
+            program_id_index: TOKEN_PROGRAM_INDEX,
+            accounts: vec![1, 3, 2, 0],
+            data: vec![255],
+        };
+        let trxs = Transactions {
+            transactions: vec![transaction(
+                1,
+                &keys,
+                vec![undecodable, transfer_checked(&keys)],
+            )],
+        };
+
+        assert!(to_events(&filter(&keys), &block(), trxs, no_multisig).is_err());
+    }
+
+    #[test]
+    fn test_to_events_resolves_multisig() {
+        let keys = Keys::new();
+        let mut transfer = transfer_checked(&keys);
+        // The destination account becomes the multisig authority, signed by the owner signing
+        // the transaction, for the sake of the test.
+        transfer.accounts = vec![1, 3, 2, 2, 0];
+        let trxs = Transactions {
+            transactions: vec![transaction(1, &keys, vec![transfer])],
+        };
+
+        let multisig = Multisig {
+            account: keys.destination.to_string(),
+            threshold: 1,
+            signers: vec![keys.owner.to_string()],
+        };
+        let events = to_events(&filter(&keys), &block(), trxs, |account| {
+            (account == multisig.account).then(|| multisig.clone())
+        })
+        .unwrap();
+
+        let signer = events.data[0].r#type.as_ref().unwrap().signer().unwrap();
+        let Some(Kind::Multisig(multi)) = signer.kind.as_ref() else {
+            panic!("expected a multisig signer, got {signer:?}");
+        };
+        assert_eq!(multi.threshold, Some(1));
+        assert_eq!(multi.registered_signers, vec![keys.owner.to_string()]);
+        assert!(multi.threshold_met);
+    }
+
+    fn close_account(account: u8, account_key: &Pubkey, keys: &Keys) -> CompiledInstruction {
+        let data =
+            instruction::close_account(&spl_token::ID, account_key, &keys.owner, &keys.owner, &[])
+                .unwrap()
+                .data;
+
+        CompiledInstruction {
+            program_id_index: TOKEN_PROGRAM_INDEX,
+            accounts: vec![account, 0, 0],
+            data,
+        }
+    }
+
+    fn closed_account(events: &Events) -> &CloseAccount {
+        events
+            .data
+            .iter()
+            .find_map(|event| match event.r#type.as_ref() {
+                Some(Type::CloseAccount(close_account)) => Some(close_account),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_to_events_closed_account_balance_at_close() {
+        let keys = Keys::new();
+        let mut trx = transaction(
+            1,
+            &keys,
+            vec![
+                transfer_checked(&keys),
+                close_account(1, &keys.source, &keys),
+            ],
+        );
+        trx.meta = Some(TransactionStatusMeta {
+            fee: 5000,
+            pre_balances: vec![1_000_000, 2_039_280, 2_039_280, 1_461_600, 1],
+            post_balances: vec![3_034_280, 0, 2_039_280, 1_461_600, 1],
+            pre_token_balances: vec![TokenBalance {
+                account_index: 1,
+                mint: keys.mint.to_string(),
+                owner: keys.owner.to_string(),
+                ui_token_amount: Some(UiTokenAmount {
+                    amount: "10".to_string(),
+                    decimals: 6,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }],
+            ..Default::default()
+        });
+        let trxs = Transactions {
+            transactions: vec![trx],
+        };
+
+        let events = to_events(&filter(&keys), &block(), trxs, no_multisig).unwrap();
+
+        // The transfer emptied the account before it was closed
+        let close_account = closed_account(&events);
+        assert_eq!(close_account.mint, keys.mint.to_string());
+        assert_eq!(close_account.amount, 0);
+        assert_eq!(close_account.reclaimed_lamports, 2_039_280);
+    }
+
+    #[test]
+    fn test_to_events_account_created_and_closed() {
+        let keys = Keys::new();
+        let initialize = CompiledInstruction {
+            program_id_index: TOKEN_PROGRAM_INDEX,
+            accounts: vec![2, 3],
+            data: instruction::initialize_account3(
+                &spl_token::ID,
+                &keys.destination,
+                &keys.mint,
+                &keys.owner,
+            )
+            .unwrap()
+            .data,
+        };
+        let mut trx = transaction(
+            1,
+            &keys,
+            vec![initialize, close_account(2, &keys.destination, &keys)],
+        );
+        // The account was funded by another program, its lamports are credited to the owner
+        trx.meta = Some(TransactionStatusMeta {
+            fee: 5000,
+            pre_balances: vec![1_000_000, 0, 0, 1_461_600, 1],
+            post_balances: vec![3_035_280, 0, 0, 1_461_600, 1],
+            ..Default::default()
+        });
+        let trxs = Transactions {
+            transactions: vec![trx],
+        };
+
+        let events = to_events(&filter(&keys), &block(), trxs, no_multisig).unwrap();
+
+        let close_account = closed_account(&events);
+        assert_eq!(close_account.mint, keys.mint.to_string());
+        assert_eq!(close_account.amount, 0);
+        assert_eq!(close_account.reclaimed_lamports, 2_040_280);
+    }
+}
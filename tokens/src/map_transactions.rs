@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+
+use crate::block_meta::to_block_meta;
+use crate::map_block::{to_events, Filter};
+use crate::pb::{
+    sf::solana::spl::token::v1::{Events, Multisig, TokenTransaction, TokenTransactions},
+    sol::transactions::v1::Transactions,
+};
+use substreams::{
+    skip_empty_output,
+    store::{StoreGet, StoreGetProto},
+};
+use substreams_solana::pb::sf::solana::r#type::v1::Block;
+
+/// Emits the events of `map_block`, taking the same params, grouped by the transaction that
+/// produced them along with its balance changes.
+#[substreams::handlers::map]
+fn map_transactions(
+    params: String,
+    block: Block,
+    multisigs: StoreGetProto<Multisig>,
+) -> Result<TokenTransactions, substreams::errors::Error> {
+    skip_empty_output();
+    let filter = Filter::from_params(&params)?;
+
+    let events = to_events(
+        &filter,
+        &to_block_meta(&block),
+        Transactions {
+            transactions: block.transactions,
+        },
+        |account| multisigs.get_last(account),
+    )?;
+
+    Ok(to_token_transactions(events))
+}
+
+/// Groups the events and balance changes of `events` under their transaction, in the order of
+/// `events.transactions`.
+fn to_token_transactions(events: Events) -> TokenTransactions {
+    let mut data: Vec<TokenTransaction> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    for transaction in events.transactions {
+        positions.insert(transaction.txn_id.clone(), data.len());
+        data.push(TokenTransaction {
+            txn_id: transaction.txn_id,
+            index: transaction.index,
+            signers: transaction.signers,
+            success: transaction.success,
+            events: vec![],
+            changes: vec![],
+        });
+    }
+
+    // `to_events` emits the transaction of every event and balance change
+    for event in events.data {
+        data[positions[&event.txn_id]].events.push(event);
+    }
+    for change in events.balance_changes {
+        data[positions[&change.txn_id]].changes.push(change);
+    }
+
+    TokenTransactions { data }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pb::sf::solana::spl::token::v1::{BalanceChange, Event, Transaction};
+
+    fn event(txn_id: &str, instruction_index: u32) -> Event {
+        Event {
+            txn_id: txn_id.to_string(),
+            instruction_index,
+            ..Default::default()
+        }
+    }
+
+    fn balance_change(txn_id: &str, account: &str) -> BalanceChange {
+        BalanceChange {
+            txn_id: txn_id.to_string(),
+            account: account.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn transaction(txn_id: &str, index: u32, success: bool) -> Transaction {
+        Transaction {
+            txn_id: txn_id.to_string(),
+            index,
+            signers: vec![format!("{txn_id}-signer")],
+            success,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_to_token_transactions() {
+        let events = Events {
+            data: vec![event("a", 0), event("a", 2), event("b", 1)],
+            balance_changes: vec![
+                balance_change("a", "source"),
+                balance_change("a", "destination"),
+                balance_change("b", "source"),
+            ],
+            transactions: vec![transaction("a", 0, true), transaction("b", 3, false)],
+        };
+
+        let transactions = to_token_transactions(events);
+
+        assert_eq!(
+            transactions.data,
+            vec![
+                TokenTransaction {
+                    txn_id: "a".to_string(),
+                    index: 0,
+                    signers: vec!["a-signer".to_string()],
+                    success: true,
+                    events: vec![event("a", 0), event("a", 2)],
+                    changes: vec![
+                        balance_change("a", "source"),
+                        balance_change("a", "destination"),
+                    ],
+                },
+                TokenTransaction {
+                    txn_id: "b".to_string(),
+                    index: 3,
+                    signers: vec!["b-signer".to_string()],
+                    success: false,
+                    events: vec![event("b", 1)],
+                    changes: vec![balance_change("b", "source")],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_token_transactions_without_balance_changes() {
+        let events = Events {
+            data: vec![event("a", 0), event("b", 0)],
+            balance_changes: vec![balance_change("b", "source")],
+            transactions: vec![transaction("a", 0, true), transaction("b", 1, true)],
+        };
+
+        let transactions = to_token_transactions(events);
+
+        assert!(transactions.data[0].changes.is_empty());
+        assert_eq!(
+            transactions.data[1].changes,
+            vec![balance_change("b", "source")]
+        );
+    }
+
+    #[test]
+    fn test_to_token_transactions_groups_by_txn_id() {
+        let events = Events {
+            data: vec![event("a", 0), event("b", 0), event("a", 1)],
+            balance_changes: vec![balance_change("b", "source"), balance_change("a", "source")],
+            transactions: vec![transaction("a", 0, true), transaction("b", 1, true)],
+        };
+
+        let transactions = to_token_transactions(events);
+
+        assert_eq!(
+            transactions.data[0].events,
+            vec![event("a", 0), event("a", 1)]
+        );
+        assert_eq!(
+            transactions.data[0].changes,
+            vec![balance_change("a", "source")]
+        );
+        assert_eq!(transactions.data[1].events, vec![event("b", 0)]);
+    }
+}
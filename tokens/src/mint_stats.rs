@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+
+use crate::pb::sf::solana::spl::token::v1::{
+    event::Type, transfer::TransferAccounts, Burn, Events, MintTo, Transfer,
+};
+use substreams::{
+    pb::substreams::{store_delta::Operation, Clock},
+    scalar::BigInt,
+    store::{
+        DeltaInt64, Deltas, StoreAdd, StoreAddBigInt, StoreDelete, StoreNew, StoreSet,
+        StoreSetIfNotExists, StoreSetIfNotExistsInt64, StoreSetInt64,
+    },
+};
+
+/// The aggregation periods of the mint statistics with their length in seconds.
+pub(crate) const PERIODS: [(&str, i64); 2] = [("hourly", 3600), ("daily", 86400)];
+
+/// Keeps the current bucket of each period, keyed by period. A delta changing the bucket
+/// carries the bucket of the previous block as its old value, which is complete however many
+/// periods passed since then.
+#[substreams::handlers::store]
+fn store_mint_stats_buckets(clock: Clock, store: StoreSetInt64) {
+    let timestamp = block_timestamp(&clock);
+    for (period, length) in PERIODS {
+        store.set(0, period, &(timestamp - timestamp % length));
+    }
+}
+
+/// Records, once per period bucket, each owner that sent or received the mint, keyed
+/// `<period>:<bucket>:<mint>:<sender|receiver>:<owner>`. Only the first write of a key
+/// produces a delta, which is what `store_mint_stats` counts unique participants from.
+#[substreams::handlers::store]
+fn store_mint_stats_participants(
+    clock: Clock,
+    events: Events,
+    buckets_deltas: Deltas<DeltaInt64>,
+    store: StoreSetIfNotExistsInt64,
+) {
+    let timestamp = block_timestamp(&clock);
+    delete_completed_buckets(&store, &buckets_deltas);
+
+    let owners = account_owners(&events);
+    for (ordinal, event) in events.data.iter().enumerate() {
+        let Some(Type::Transfer(Transfer { accounts, .. })) = event.r#type.as_ref() else {
+            continue;
+        };
+        let accounts = accounts.as_ref().unwrap();
+        let Some(mint) = transfer_mint(&events, accounts) else {
+            continue;
+        };
+
+        let sender = owners
+            .get(accounts.source.as_str())
+            .copied()
+            .unwrap_or(&accounts.source);
+        let receiver = owners
+            .get(accounts.destination.as_str())
+            .copied()
+            .unwrap_or(&accounts.destination);
+
+        for (period, length) in PERIODS {
+            let key = bucket_key(period, timestamp - timestamp % length, mint);
+            store.set_if_not_exists(ordinal as u64, format!("{key}:sender:{sender}"), &1);
+            store.set_if_not_exists(ordinal as u64, format!("{key}:receiver:{receiver}"), &1);
+        }
+    }
+}
+
+/// Accumulates the statistics of each mint per period bucket, keyed
+/// `<period>:<bucket>:<mint>:<metric>`, the bucket being the start timestamp of the period.
+#[substreams::handlers::store]
+fn store_mint_stats(
+    clock: Clock,
+    events: Events,
+    buckets_deltas: Deltas<DeltaInt64>,
+    participants_deltas: Deltas<DeltaInt64>,
+    store: StoreAddBigInt,
+) {
+    let timestamp = block_timestamp(&clock);
+    delete_completed_buckets(&store, &buckets_deltas);
+
+    for (ordinal, event) in events.data.iter().enumerate() {
+        let Some(event_type) = event.r#type.as_ref() else {
+            continue;
+        };
+        let (mint, metrics): (&str, Vec<(&str, BigInt)>) = match event_type {
+            Type::Transfer(Transfer {
+                instruction,
+                accounts,
+                ..
+            }) => match transfer_mint(&events, accounts.as_ref().unwrap()) {
+                Some(mint) => (
+                    mint,
+                    vec![
+                        ("transfer_count", BigInt::from(1u64)),
+                        (
+                            "transfer_volume",
+                            BigInt::from(instruction.as_ref().unwrap().amount),
+                        ),
+                    ],
+                ),
+                None => continue,
+            },
+            Type::MintTo(MintTo {
+                instruction,
+                accounts,
+            }) => (
+                accounts.as_ref().unwrap().mint.as_str(),
+                vec![(
+                    "mint_volume",
+                    BigInt::from(instruction.as_ref().unwrap().amount),
+                )],
+            ),
+            Type::Burn(Burn {
+                instruction,
+                accounts,
+            }) => (
+                accounts.as_ref().unwrap().mint.as_str(),
+                vec![(
+                    "burn_volume",
+                    BigInt::from(instruction.as_ref().unwrap().amount),
+                )],
+            ),
+            _ => continue,
+        };
+
+        for (period, length) in PERIODS {
+            let key = bucket_key(period, timestamp - timestamp % length, mint);
+            for (metric, value) in &metrics {
+                store.add(ordinal as u64, format!("{key}:{metric}"), value.clone());
+            }
+        }
+    }
+
+    for delta in participants_deltas.deltas {
+        if delta.operation != Operation::Create {
+            continue;
+        }
+
+        // <period>:<bucket>:<mint>:<sender|receiver>:<owner>
+        let segments: Vec<&str> = delta.key.split(':').collect();
+        let metric = match segments[3] {
+            "sender" => "unique_senders",
+            _ => "unique_receivers",
+        };
+
+        store.add(
+            delta.ordinal,
+            format!("{}:{metric}", segments[..3].join(":")),
+            BigInt::from(1u64),
+        );
+    }
+}
+
+pub(crate) fn bucket_key(period: &str, bucket: i64, mint: &str) -> String {
+    format!("{period}:{bucket}:{mint}")
+}
+
+/// Deletes the keys of the buckets completed since the previous block, as told by the deltas
+/// of `store_mint_stats_buckets`. They were already emitted as deltas, so they don't need to
+/// be kept in the store anymore.
+fn delete_completed_buckets<S: StoreDelete>(store: &S, buckets_deltas: &Deltas<DeltaInt64>) {
+    for delta in &buckets_deltas.deltas {
+        if delta.operation == Operation::Update && delta.old_value != delta.new_value {
+            store.delete_prefix(0, &format!("{}:{}:", delta.key, delta.old_value));
+        }
+    }
+}
+
+fn block_timestamp(clock: &Clock) -> i64 {
+    clock
+        .timestamp
+        .as_ref()
+        .map(|t| t.seconds)
+        .unwrap_or_default()
+}
+
+/// Returns the mint of the transfer, from the instruction itself for `TransferChecked`, or
+/// from the token balances of the source account otherwise.
+fn transfer_mint<'a>(events: &'a Events, accounts: &'a TransferAccounts) -> Option<&'a str> {
+    match &accounts.token_mint {
+        Some(token_mint) => Some(token_mint),
+        None => events
+            .balance_changes
+            .iter()
+            .find(|change| change.account == accounts.source)
+            .map(|change| change.mint.as_str()),
+    }
+}
+
+/// Maps the token accounts found in the balance changes to their owner.
+fn account_owners(events: &Events) -> HashMap<&str, &String> {
+    events
+        .balance_changes
+        .iter()
+        .map(|change| (change.account.as_str(), &change.owner))
+        .collect()
+}
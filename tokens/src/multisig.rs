@@ -0,0 +1,64 @@
+use crate::instructions::{decode_instruction, TransactionSigners};
+use crate::pb::{
+    sf::solana::spl::token::v1::{event::Type, signer::Kind, InitializeMultisig, Multisig, Signer},
+    sol::transactions::v1::Transactions,
+};
+use substreams::store::{StoreNew, StoreSet, StoreSetProto};
+
+/// Registers every multisig account initialized by a successful transaction, keyed by the
+/// multisig account, regardless of the `token_contract` parameter of `map_block` since a
+/// multisig isn't tied to any mint.
+#[substreams::handlers::store]
+fn store_multisigs(trxs: Transactions, store: StoreSetProto<Multisig>) {
+    for confirmed_txn in trxs.transactions {
+        match confirmed_txn.meta.as_ref() {
+            Some(meta) if meta.err.is_none() => {}
+            _ => continue,
+        }
+
+        let signers = TransactionSigners::new(&confirmed_txn);
+        for instruction in confirmed_txn.walk_instructions() {
+            let Ok(Some(Type::InitializeMultisig(InitializeMultisig {
+                instruction: initialize,
+                accounts,
+                ..
+            }))) = decode_instruction(&instruction, &signers)
+            else {
+                continue;
+            };
+
+            let accounts = accounts.unwrap();
+            store.set(
+                0,
+                &accounts.account,
+                &Multisig {
+                    account: accounts.account.clone(),
+                    threshold: initialize.unwrap().signature_count_threshold,
+                    signers: accounts.signers,
+                },
+            );
+        }
+    }
+}
+
+/// Annotates a multisig signer with the threshold of its multisig account, the signers of
+/// the instruction that belong to its signer set and whether they are enough to meet the
+/// threshold, the multisig account being looked up with `multisig`. Single signers and unknown
+/// multisig accounts are left untouched.
+pub(crate) fn resolve_multisig(signer: &mut Signer, multisig: impl Fn(&str) -> Option<Multisig>) {
+    let Some(Kind::Multisig(multi)) = signer.kind.as_mut() else {
+        return;
+    };
+    let Some(multisig) = multisig(&multi.multisig_account) else {
+        return;
+    };
+
+    multi.registered_signers = multi
+        .signers
+        .iter()
+        .filter(|signer| multisig.signers.contains(signer))
+        .cloned()
+        .collect();
+    multi.threshold = Some(multisig.threshold);
+    multi.threshold_met = multi.registered_signers.len() >= multisig.threshold as usize;
+}
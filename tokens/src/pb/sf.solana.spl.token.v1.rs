@@ -1,30 +1,102 @@
 // @generated
 // This file is @generated by prost-build.
 #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Events {
     #[prost(message, repeated, tag="1")]
     pub data: ::prost::alloc::vec::Vec<Event>,
+    /// The token balances, before and after the transaction, of the accounts holding the
+    /// tracked token contract, for each transaction that produced at least one event in `data`.
+    #[prost(message, repeated, tag="2")]
+    pub balance_changes: ::prost::alloc::vec::Vec<BalanceChange>,
+    /// The transactions that produced at least one event in `data`, join them on `txn_id`.
+    #[prost(message, repeated, tag="3")]
+    pub transactions: ::prost::alloc::vec::Vec<Transaction>,
 }
+/// The header of the block being processed, the output of 'map_block_meta'.
 #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BlockMeta {
+    #[prost(uint64, tag="1")]
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::u64_string"))]
+    pub slot: u64,
+    /// The number of blocks produced before this one, which lags behind the slot as slots can be
+    /// skipped. Zero for blocks recorded before the block height was tracked.
+    #[prost(uint64, tag="2")]
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::u64_string"))]
+    pub block_height: u64,
+    #[prost(uint64, tag="3")]
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::u64_string"))]
+    pub parent_slot: u64,
+    #[prost(string, tag="4")]
+    pub block_hash: ::prost::alloc::string::String,
+    #[prost(string, tag="5")]
+    pub previous_block_hash: ::prost::alloc::string::String,
+    #[prost(int64, tag="6")]
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::i64_string"))]
+    pub block_timestamp: i64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Event {
     #[prost(string, tag="101")]
     pub txn_id: ::prost::alloc::string::String,
+    /// The number of blocks produced before this one, which lags behind the slot as slots can be
+    /// skipped. Zero for blocks recorded before the block height was tracked.
     #[prost(uint64, tag="102")]
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::u64_string"))]
     pub block_height: u64,
     #[prost(int64, tag="103")]
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::i64_string"))]
     pub block_timestamp: i64,
     #[prost(string, tag="104")]
     pub block_hash: ::prost::alloc::string::String,
     #[prost(uint32, tag="106")]
     pub instruction_index: u32,
-    #[prost(oneof="event::Type", tags="10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 22, 23, 24")]
+    /// Index of the top-level instruction of the transaction this instruction is, or is invoked by
+    #[prost(uint32, tag="107")]
+    pub outer_instruction_index: u32,
+    /// Index of the instruction within the inner instructions of its top-level instruction,
+    /// unset when the instruction is itself a top-level instruction
+    #[prost(uint32, optional, tag="108")]
+    pub inner_instruction_index: ::core::option::Option<u32>,
+    /// The invocation depth of the instruction, 1 for top-level instructions
+    #[prost(uint32, tag="109")]
+    pub stack_height: u32,
+    /// The program that invoked this instruction through CPI, unset for top-level instructions
+    #[prost(string, optional, tag="110")]
+    pub parent_program_id: ::core::option::Option<::prost::alloc::string::String>,
+    /// The original instruction, telling apart the instructions represented by the same message,
+    /// e.g. 'Transfer' and 'TransferChecked'
+    #[prost(enumeration="event::InstructionKind", tag="111")]
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::instruction_kind"))]
+    pub instruction_kind: i32,
+    /// The program, ordered accounts and data of the instruction, only set when the
+    /// 'raw_instructions' param of 'map_block' is enabled
+    #[prost(message, optional, tag="112")]
+    pub raw_instruction: ::core::option::Option<super::super::super::super::super::sol::instructions::v1::Instruction>,
+    /// The slot of the block, what 'sf.substreams.v1.Clock' reports as the block number
+    #[prost(uint64, tag="113")]
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::u64_string"))]
+    pub slot: u64,
+    #[prost(uint64, tag="114")]
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::u64_string"))]
+    pub parent_slot: u64,
+    #[prost(string, tag="115")]
+    pub previous_block_hash: ::prost::alloc::string::String,
+    /// Unset for the instructions that aren't modeled, only emitted along with their
+    /// 'raw_instruction' when the 'raw_instructions' param of 'map_block' is enabled
+    #[prost(oneof="event::Type", tags="10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 22, 23, 24, 25, 26")]
     pub r#type: ::core::option::Option<event::Type>,
 }
 /// Nested message and enum types in `Event`.
 pub mod event {
     #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 #[derive(Clone, PartialEq, ::prost::Oneof)]
     pub enum Type {
         #[prost(message, tag="10")]
@@ -55,31 +127,152 @@ pub mod event {
         ThawAccount(super::ThawAccount),
         #[prost(message, tag="24")]
         SyncNative(super::SyncNative),
+        #[prost(message, tag="25")]
+        AssociatedAccountCreated(super::AssociatedAccountCreated),
+        #[prost(message, tag="26")]
+        AssociatedAccountNestedRecovered(super::AssociatedAccountNestedRecovered),
+    }
+    /// The original instruction of the event. The SPL Token instructions are numbered after
+    /// their discriminator plus one, the Associated Token Account ones start at 100.
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+    #[repr(i32)]
+    pub enum InstructionKind {
+        Unspecified = 0,
+        InitializeMint = 1,
+        InitializeAccount = 2,
+        InitializeMultisig = 3,
+        Transfer = 4,
+        Approve = 5,
+        Revoke = 6,
+        SetAuthority = 7,
+        MintTo = 8,
+        Burn = 9,
+        CloseAccount = 10,
+        FreezeAccount = 11,
+        ThawAccount = 12,
+        TransferChecked = 13,
+        ApproveChecked = 14,
+        MintToChecked = 15,
+        BurnChecked = 16,
+        InitializeAccount2 = 17,
+        SyncNative = 18,
+        InitializeAccount3 = 19,
+        InitializeMultisig2 = 20,
+        InitializeMint2 = 21,
+        GetAccountDataSize = 22,
+        InitializeImmutableOwner = 23,
+        AmountToUiAmount = 24,
+        UiAmountToAmount = 25,
+        AssociatedAccountCreate = 100,
+        AssociatedAccountCreateIdempotent = 101,
+        AssociatedAccountRecoverNested = 102,
+    }
+    impl InstructionKind {
+        /// String value of the enum field names used in the ProtoBuf definition.
+        ///
+        /// The values are not transformed in any way and thus are considered stable
+        /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+        pub fn as_str_name(&self) -> &'static str {
+            match self {
+                InstructionKind::Unspecified => "InstructionKindUnspecified",
+                InstructionKind::InitializeMint => "InitializeMint",
+                InstructionKind::InitializeAccount => "InitializeAccount",
+                InstructionKind::InitializeMultisig => "InitializeMultisig",
+                InstructionKind::Transfer => "Transfer",
+                InstructionKind::Approve => "Approve",
+                InstructionKind::Revoke => "Revoke",
+                InstructionKind::SetAuthority => "SetAuthority",
+                InstructionKind::MintTo => "MintTo",
+                InstructionKind::Burn => "Burn",
+                InstructionKind::CloseAccount => "CloseAccount",
+                InstructionKind::FreezeAccount => "FreezeAccount",
+                InstructionKind::ThawAccount => "ThawAccount",
+                InstructionKind::TransferChecked => "TransferChecked",
+                InstructionKind::ApproveChecked => "ApproveChecked",
+                InstructionKind::MintToChecked => "MintToChecked",
+                InstructionKind::BurnChecked => "BurnChecked",
+                InstructionKind::InitializeAccount2 => "InitializeAccount2",
+                InstructionKind::SyncNative => "SyncNative",
+                InstructionKind::InitializeAccount3 => "InitializeAccount3",
+                InstructionKind::InitializeMultisig2 => "InitializeMultisig2",
+                InstructionKind::InitializeMint2 => "InitializeMint2",
+                InstructionKind::GetAccountDataSize => "GetAccountDataSize",
+                InstructionKind::InitializeImmutableOwner => "InitializeImmutableOwner",
+                InstructionKind::AmountToUiAmount => "AmountToUiAmount",
+                InstructionKind::UiAmountToAmount => "UiAmountToAmount",
+                InstructionKind::AssociatedAccountCreate => "AssociatedAccountCreate",
+                InstructionKind::AssociatedAccountCreateIdempotent => "AssociatedAccountCreateIdempotent",
+                InstructionKind::AssociatedAccountRecoverNested => "AssociatedAccountRecoverNested",
+            }
+        }
+        /// Creates an enum from field names used in the ProtoBuf definition.
+        pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+            match value {
+                "InstructionKindUnspecified" => Some(Self::Unspecified),
+                "InitializeMint" => Some(Self::InitializeMint),
+                "InitializeAccount" => Some(Self::InitializeAccount),
+                "InitializeMultisig" => Some(Self::InitializeMultisig),
+                "Transfer" => Some(Self::Transfer),
+                "Approve" => Some(Self::Approve),
+                "Revoke" => Some(Self::Revoke),
+                "SetAuthority" => Some(Self::SetAuthority),
+                "MintTo" => Some(Self::MintTo),
+                "Burn" => Some(Self::Burn),
+                "CloseAccount" => Some(Self::CloseAccount),
+                "FreezeAccount" => Some(Self::FreezeAccount),
+                "ThawAccount" => Some(Self::ThawAccount),
+                "TransferChecked" => Some(Self::TransferChecked),
+                "ApproveChecked" => Some(Self::ApproveChecked),
+                "MintToChecked" => Some(Self::MintToChecked),
+                "BurnChecked" => Some(Self::BurnChecked),
+                "InitializeAccount2" => Some(Self::InitializeAccount2),
+                "SyncNative" => Some(Self::SyncNative),
+                "InitializeAccount3" => Some(Self::InitializeAccount3),
+                "InitializeMultisig2" => Some(Self::InitializeMultisig2),
+                "InitializeMint2" => Some(Self::InitializeMint2),
+                "GetAccountDataSize" => Some(Self::GetAccountDataSize),
+                "InitializeImmutableOwner" => Some(Self::InitializeImmutableOwner),
+                "AmountToUiAmount" => Some(Self::AmountToUiAmount),
+                "UiAmountToAmount" => Some(Self::UiAmountToAmount),
+                "AssociatedAccountCreate" => Some(Self::AssociatedAccountCreate),
+                "AssociatedAccountCreateIdempotent" => Some(Self::AssociatedAccountCreateIdempotent),
+                "AssociatedAccountRecoverNested" => Some(Self::AssociatedAccountRecoverNested),
+                _ => None,
+            }
+        }
     }
 }
 /// The instructions 'Transfer' and 'TransferChecked' are both represented by this message.
-/// You can check if the instructions was 'TransferChecked' by inspecting the 'accounts.token_mint',
-/// if set, it was a 'TransferChecked' instruction.
+/// The original instruction is given by the 'instruction_kind' of the event.
 #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Transfer {
     #[prost(message, optional, tag="1")]
     pub instruction: ::core::option::Option<transfer::TransferInstruction>,
     #[prost(message, optional, tag="2")]
     pub accounts: ::core::option::Option<transfer::TransferAccounts>,
+    /// The text of the SPL Memo (v1 or v2) instructions of the transaction, joined by "; " when
+    /// there is more than one. Unset if the transaction has no memo.
+    #[prost(string, optional, tag="3")]
+    pub memo: ::core::option::Option<::prost::alloc::string::String>,
 }
 /// Nested message and enum types in `Transfer`.
 pub mod transfer {
     #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
     pub struct TransferInstruction {
         #[prost(uint64, tag="1")]
+        #[cfg_attr(feature = "serde", serde(with = "crate::json::u64_string"))]
         pub amount: u64,
         /// Only set if the instruction was 'TransferChecked'
         #[prost(uint32, optional, tag="2")]
         pub decimals: ::core::option::Option<u32>,
     }
     #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, ::prost::Message)]
     pub struct TransferAccounts {
         #[prost(string, tag="1")]
@@ -94,9 +287,11 @@ pub mod transfer {
     }
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct InitializeMint {
     #[prost(enumeration="initialize_mint::InitializeMintVersion", tag="1")]
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::initialize_mint_version"))]
     pub version: i32,
     #[prost(message, optional, tag="2")]
     pub instruction: ::core::option::Option<initialize_mint::InitializeMintInstruction>,
@@ -106,6 +301,7 @@ pub struct InitializeMint {
 /// Nested message and enum types in `InitializeMint`.
 pub mod initialize_mint {
     #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, ::prost::Message)]
     pub struct InitializeMintInstruction {
         #[prost(uint32, tag="1")]
@@ -116,11 +312,13 @@ pub mod initialize_mint {
         pub freeze_authority: ::core::option::Option<::prost::alloc::string::String>,
     }
     #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, ::prost::Message)]
     pub struct InitializeMintAccounts {
         #[prost(string, tag="1")]
         pub mint: ::prost::alloc::string::String,
     }
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
     #[repr(i32)]
     pub enum InitializeMintVersion {
@@ -160,22 +358,30 @@ pub mod initialize_mint {
 /// You can use the `version` field (1 or 2) to determine which instruction
 /// is being used.
 #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct InitializeAccount {
     #[prost(enumeration="initialize_account::InitializeAccountVersion", tag="1")]
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::initialize_account_version"))]
     pub version: i32,
     #[prost(message, optional, tag="2")]
     pub instruction: ::core::option::Option<initialize_account::InitializeAccountInstruction>,
     #[prost(message, optional, tag="3")]
     pub accounts: ::core::option::Option<initialize_account::InitializeAccountAccounts>,
+    /// Set if the account was initialized by the Associated Token Account program, in which case
+    /// it is the canonical associated token account of its owner for the mint
+    #[prost(bool, tag="4")]
+    pub is_associated_account: bool,
 }
 /// Nested message and enum types in `InitializeAccount`.
 pub mod initialize_account {
     #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
     pub struct InitializeAccountInstruction {
     }
     #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, ::prost::Message)]
     pub struct InitializeAccountAccounts {
         #[prost(string, tag="1")]
@@ -185,6 +391,7 @@ pub mod initialize_account {
         #[prost(string, tag="3")]
         pub owner: ::prost::alloc::string::String,
     }
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
     #[repr(i32)]
     pub enum InitializeAccountVersion {
@@ -219,9 +426,11 @@ pub mod initialize_account {
     }
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct InitializeMultisig {
     #[prost(enumeration="initialize_multisig::InitializeMultisigVersion", tag="1")]
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::initialize_multisig_version"))]
     pub version: i32,
     #[prost(message, optional, tag="2")]
     pub instruction: ::core::option::Option<initialize_multisig::InitializeMultisigInstruction>,
@@ -231,6 +440,7 @@ pub struct InitializeMultisig {
 /// Nested message and enum types in `InitializeMultisig`.
 pub mod initialize_multisig {
     #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
     pub struct InitializeMultisigInstruction {
         /// The number of signers required to approve transactions, equivalent to `m` in SPL Token
@@ -238,6 +448,7 @@ pub mod initialize_multisig {
         pub signature_count_threshold: u32,
     }
     #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, ::prost::Message)]
     pub struct InitializeMultisigAccounts {
         #[prost(string, tag="1")]
@@ -245,6 +456,7 @@ pub mod initialize_multisig {
         #[prost(string, repeated, tag="2")]
         pub signers: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
     }
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
     #[repr(i32)]
     pub enum InitializeMultisigVersion {
@@ -276,9 +488,9 @@ pub mod initialize_multisig {
     }
 }
 /// The instructions 'Approve' and 'ApproveChecked' are both represented by this message.
-/// You can check if the instructions was 'ApproveChecked' by inspecting the 'accounts.token_mint',
-/// if set, it was a 'ApproveChecked' instruction.
+/// The original instruction is given by the 'instruction_kind' of the event.
 #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Approve {
     #[prost(message, optional, tag="1")]
@@ -289,15 +501,18 @@ pub struct Approve {
 /// Nested message and enum types in `Approve`.
 pub mod approve {
     #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
     pub struct ApproveInstruction {
         #[prost(uint64, tag="1")]
+        #[cfg_attr(feature = "serde", serde(with = "crate::json::u64_string"))]
         pub amount: u64,
         /// Only set if the instruction was 'ApproveChecked'
         #[prost(uint32, optional, tag="2")]
         pub decimals: ::core::option::Option<u32>,
     }
     #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, ::prost::Message)]
     pub struct ApproveAccounts {
         #[prost(string, tag="1")]
@@ -312,6 +527,7 @@ pub mod approve {
     }
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Revoke {
     #[prost(message, optional, tag="1")]
@@ -322,10 +538,12 @@ pub struct Revoke {
 /// Nested message and enum types in `Revoke`.
 pub mod revoke {
     #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
     pub struct RevokeInstruction {
     }
     #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, ::prost::Message)]
     pub struct RevokeAccounts {
         #[prost(string, tag="1")]
@@ -335,6 +553,7 @@ pub mod revoke {
     }
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SetAuthority {
     #[prost(message, optional, tag="1")]
@@ -345,21 +564,31 @@ pub struct SetAuthority {
 /// Nested message and enum types in `SetAuthority`.
 pub mod set_authority {
     #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, ::prost::Message)]
     pub struct SetAuthorityInstruction {
         #[prost(enumeration="AuthorityType", tag="1")]
+        #[cfg_attr(feature = "serde", serde(with = "crate::json::authority_type"))]
         pub authority_type: i32,
         #[prost(string, optional, tag="2")]
         pub new_authority: ::core::option::Option<::prost::alloc::string::String>,
     }
     #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, ::prost::Message)]
     pub struct SetAuthorityAccounts {
         #[prost(string, tag="1")]
         pub account: ::prost::alloc::string::String,
         #[prost(string, tag="2")]
         pub current_authority: ::prost::alloc::string::String,
+        #[prost(enumeration="AccountKind", tag="3")]
+        #[cfg_attr(feature = "serde", serde(with = "crate::json::account_kind"))]
+        pub account_kind: i32,
+        /// The current authority, along with its signers when it is a multisig account
+        #[prost(message, optional, tag="4")]
+        pub signer: ::core::option::Option<super::Signer>,
     }
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
     #[repr(i32)]
     pub enum AuthorityType {
@@ -395,11 +624,42 @@ pub mod set_authority {
             }
         }
     }
+    /// Whether 'account' is a mint or a token account, which follows from the authority type
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+    #[repr(i32)]
+    pub enum AccountKind {
+        Unspecified = 0,
+        Mint = 1,
+        TokenAccount = 2,
+    }
+    impl AccountKind {
+        /// String value of the enum field names used in the ProtoBuf definition.
+        ///
+        /// The values are not transformed in any way and thus are considered stable
+        /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+        pub fn as_str_name(&self) -> &'static str {
+            match self {
+                AccountKind::Unspecified => "AccountKindUnspecified",
+                AccountKind::Mint => "AccountKindMint",
+                AccountKind::TokenAccount => "AccountKindTokenAccount",
+            }
+        }
+        /// Creates an enum from field names used in the ProtoBuf definition.
+        pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+            match value {
+                "AccountKindUnspecified" => Some(Self::Unspecified),
+                "AccountKindMint" => Some(Self::Mint),
+                "AccountKindTokenAccount" => Some(Self::TokenAccount),
+                _ => None,
+            }
+        }
+    }
 }
 /// The instructions 'MintTo' and 'MintToChecked' are both represented by this message.
-/// You can check if the instructions was 'MintToChecked' by inspecting the
-/// 'instruction.decimals' field. If set, it was a 'MintToChecked' instruction.
+/// The original instruction is given by the 'instruction_kind' of the event.
 #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct MintTo {
     #[prost(message, optional, tag="1")]
@@ -410,15 +670,18 @@ pub struct MintTo {
 /// Nested message and enum types in `MintTo`.
 pub mod mint_to {
     #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
     pub struct MintToInstruction {
         #[prost(uint64, tag="1")]
+        #[cfg_attr(feature = "serde", serde(with = "crate::json::u64_string"))]
         pub amount: u64,
         /// Only set if the instruction was 'MintToChecked'
         #[prost(uint32, optional, tag="2")]
         pub decimals: ::core::option::Option<u32>,
     }
     #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, ::prost::Message)]
     pub struct MintToAccounts {
         #[prost(string, tag="1")]
@@ -430,9 +693,9 @@ pub mod mint_to {
     }
 }
 /// The instructions 'Burn' and 'BurnChecked' are both represented by this message.
-/// You can check if the instructions was 'BurnChecked' by inspecting the
-/// 'instruction.decimals' field. If set, it was a 'BurnChecked' instruction.
+/// The original instruction is given by the 'instruction_kind' of the event.
 #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Burn {
     #[prost(message, optional, tag="1")]
@@ -443,15 +706,18 @@ pub struct Burn {
 /// Nested message and enum types in `Burn`.
 pub mod burn {
     #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
     pub struct BurnInstruction {
         #[prost(uint64, tag="1")]
+        #[cfg_attr(feature = "serde", serde(with = "crate::json::u64_string"))]
         pub amount: u64,
         /// Only set if the instruction was 'BurnChecked'
         #[prost(uint32, optional, tag="2")]
         pub decimals: ::core::option::Option<u32>,
     }
     #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, ::prost::Message)]
     pub struct BurnAccounts {
         #[prost(string, tag="1")]
@@ -463,20 +729,36 @@ pub mod burn {
     }
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CloseAccount {
     #[prost(message, optional, tag="1")]
     pub instruction: ::core::option::Option<close_account::CloseAccountInstruction>,
     #[prost(message, optional, tag="2")]
     pub accounts: ::core::option::Option<close_account::CloseAccountAccounts>,
+    /// The mint of the closed account and its token balance when it was closed, a non-zero
+    /// amount only being possible for an account of the native mint
+    #[prost(string, tag="3")]
+    pub mint: ::prost::alloc::string::String,
+    #[prost(uint64, tag="4")]
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::u64_string"))]
+    pub amount: u64,
+    /// The lamports returned to the destination, that is the balance of the closed account when
+    /// the transaction started less its balance when it ended, or for an account created by the
+    /// transaction the lamports credited to the destination
+    #[prost(uint64, tag="5")]
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::u64_string"))]
+    pub reclaimed_lamports: u64,
 }
 /// Nested message and enum types in `CloseAccount`.
 pub mod close_account {
     #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
     pub struct CloseAccountInstruction {
     }
     #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, ::prost::Message)]
     pub struct CloseAccountAccounts {
         #[prost(string, tag="1")]
@@ -488,6 +770,7 @@ pub mod close_account {
     }
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct FreezeAccount {
     #[prost(message, optional, tag="1")]
@@ -498,10 +781,12 @@ pub struct FreezeAccount {
 /// Nested message and enum types in `FreezeAccount`.
 pub mod freeze_account {
     #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
     pub struct FreezeAccountInstruction {
     }
     #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, ::prost::Message)]
     pub struct FreezeAccountAccounts {
         #[prost(string, tag="1")]
@@ -513,6 +798,7 @@ pub mod freeze_account {
     }
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ThawAccount {
     #[prost(message, optional, tag="1")]
@@ -523,10 +809,12 @@ pub struct ThawAccount {
 /// Nested message and enum types in `ThawAccount`.
 pub mod thaw_account {
     #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
     pub struct ThawAccountInstruction {
     }
     #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, ::prost::Message)]
     pub struct ThawAccountAccounts {
         #[prost(string, tag="1")]
@@ -538,6 +826,7 @@ pub mod thaw_account {
     }
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct InitializeImmutableOwner {
     #[prost(message, optional, tag="1")]
@@ -548,10 +837,12 @@ pub struct InitializeImmutableOwner {
 /// Nested message and enum types in `InitializeImmutableOwner`.
 pub mod initialize_immutable_owner {
     #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
     pub struct InitializeImmutableOwnerInstruction {
     }
     #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, ::prost::Message)]
     pub struct InitializeImmutableOwnerAccounts {
         #[prost(string, tag="1")]
@@ -559,6 +850,7 @@ pub mod initialize_immutable_owner {
     }
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SyncNative {
     #[prost(message, optional, tag="1")]
@@ -569,17 +861,97 @@ pub struct SyncNative {
 /// Nested message and enum types in `SyncNative`.
 pub mod sync_native {
     #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
     pub struct SyncNativeInstruction {
     }
     #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, ::prost::Message)]
     pub struct SyncNativeAccounts {
         #[prost(string, tag="1")]
         pub native_token_account: ::prost::alloc::string::String,
     }
 }
+/// The 'Create' and 'CreateIdempotent' instructions of the Associated Token Account program
+/// are both represented by this message. Note that a 'CreateIdempotent' instruction for an
+/// account that already exists succeeds without creating anything.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AssociatedAccountCreated {
+    #[prost(message, optional, tag="1")]
+    pub instruction: ::core::option::Option<associated_account_created::AssociatedAccountCreatedInstruction>,
+    #[prost(message, optional, tag="2")]
+    pub accounts: ::core::option::Option<associated_account_created::AssociatedAccountCreatedAccounts>,
+}
+/// Nested message and enum types in `AssociatedAccountCreated`.
+pub mod associated_account_created {
+    #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+    pub struct AssociatedAccountCreatedInstruction {
+        /// Set if the instruction was 'CreateIdempotent'
+        #[prost(bool, tag="1")]
+        pub idempotent: bool,
+    }
+    #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct AssociatedAccountCreatedAccounts {
+        #[prost(string, tag="1")]
+        pub payer: ::prost::alloc::string::String,
+        #[prost(string, tag="2")]
+        pub associated_account: ::prost::alloc::string::String,
+        #[prost(string, tag="3")]
+        pub wallet: ::prost::alloc::string::String,
+        #[prost(string, tag="4")]
+        pub mint: ::prost::alloc::string::String,
+        #[prost(string, tag="5")]
+        pub token_program: ::prost::alloc::string::String,
+    }
+}
+/// The 'RecoverNested' instruction of the Associated Token Account program, which transfers
+/// the tokens of an associated token account owned by another associated token account of
+/// the same wallet back to the wallet, and closes it.
 #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AssociatedAccountNestedRecovered {
+    #[prost(message, optional, tag="1")]
+    pub instruction: ::core::option::Option<associated_account_nested_recovered::AssociatedAccountNestedRecoveredInstruction>,
+    #[prost(message, optional, tag="2")]
+    pub accounts: ::core::option::Option<associated_account_nested_recovered::AssociatedAccountNestedRecoveredAccounts>,
+}
+/// Nested message and enum types in `AssociatedAccountNestedRecovered`.
+pub mod associated_account_nested_recovered {
+    #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+    pub struct AssociatedAccountNestedRecoveredInstruction {
+    }
+    #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct AssociatedAccountNestedRecoveredAccounts {
+        #[prost(string, tag="1")]
+        pub nested_account: ::prost::alloc::string::String,
+        #[prost(string, tag="2")]
+        pub nested_mint: ::prost::alloc::string::String,
+        #[prost(string, tag="3")]
+        pub destination_account: ::prost::alloc::string::String,
+        #[prost(string, tag="4")]
+        pub owner_account: ::prost::alloc::string::String,
+        #[prost(string, tag="5")]
+        pub owner_mint: ::prost::alloc::string::String,
+        #[prost(string, tag="6")]
+        pub wallet: ::prost::alloc::string::String,
+        #[prost(string, tag="7")]
+        pub token_program: ::prost::alloc::string::String,
+    }
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Signer {
     #[prost(oneof="signer::Kind", tags="1, 2")]
@@ -588,6 +960,8 @@ pub struct Signer {
 /// Nested message and enum types in `Signer`.
 pub mod signer {
     #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 #[derive(Clone, PartialEq, ::prost::Oneof)]
     pub enum Kind {
         #[prost(message, tag="1")]
@@ -597,17 +971,317 @@ pub mod signer {
     }
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SingleSignature {
     #[prost(string, tag="1")]
     pub signer: ::prost::alloc::string::String,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct MultiSignature {
     #[prost(string, tag="1")]
     pub multisig_account: ::prost::alloc::string::String,
     #[prost(string, repeated, tag="2")]
     pub signers: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// The fields below are resolved from the 'InitializeMultisig' of the multisig account and
+    /// are left unset when it was initialized before the first processed block
+    #[prost(uint32, optional, tag="3")]
+    pub threshold: ::core::option::Option<u32>,
+    /// The signers of the instruction that belong to the signer set of the multisig
+    #[prost(string, repeated, tag="4")]
+    pub registered_signers: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    #[prost(bool, tag="5")]
+    pub threshold_met: bool,
+}
+/// A multisig account as initialized by 'InitializeMultisig', the value of the
+/// 'store_multisigs' store
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Multisig {
+    #[prost(string, tag="1")]
+    pub account: ::prost::alloc::string::String,
+    #[prost(uint32, tag="2")]
+    pub threshold: u32,
+    #[prost(string, repeated, tag="3")]
+    pub signers: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BalanceChange {
+    #[prost(string, tag="1")]
+    pub txn_id: ::prost::alloc::string::String,
+    #[prost(string, tag="2")]
+    pub account: ::prost::alloc::string::String,
+    #[prost(string, tag="3")]
+    pub mint: ::prost::alloc::string::String,
+    #[prost(string, tag="4")]
+    pub owner: ::prost::alloc::string::String,
+    #[prost(uint64, tag="5")]
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::u64_string"))]
+    pub pre_amount: u64,
+    /// Zero when the account was closed by the transaction
+    #[prost(uint64, tag="6")]
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::u64_string"))]
+    pub post_amount: u64,
+    #[prost(uint32, tag="7")]
+    pub decimals: u32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Transaction {
+    #[prost(string, tag="1")]
+    pub txn_id: ::prost::alloc::string::String,
+    /// Index of the transaction in the block, vote transactions included
+    #[prost(uint32, tag="2")]
+    pub index: u32,
+    /// The account paying the fees, which is the first signer of the transaction
+    #[prost(string, tag="3")]
+    pub fee_payer: ::prost::alloc::string::String,
+    #[prost(string, repeated, tag="4")]
+    pub signers: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    #[prost(uint64, tag="5")]
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::u64_string"))]
+    pub fee: u64,
+    /// Not available for transactions recorded before compute units were tracked
+    #[prost(uint64, optional, tag="6")]
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::option_u64_string"))]
+    pub compute_units_consumed: ::core::option::Option<u64>,
+    #[prost(string, tag="7")]
+    pub recent_blockhash: ::prost::alloc::string::String,
+    /// Unset when the transaction failed, in which case none of its events took effect
+    #[prost(bool, tag="8")]
+    pub success: bool,
+}
+/// The output of 'map_transactions', the events of 'map_block' grouped by transaction.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TokenTransactions {
+    #[prost(message, repeated, tag="1")]
+    pub data: ::prost::alloc::vec::Vec<TokenTransaction>,
+}
+/// A transaction that produced at least one event for the tracked token contract.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TokenTransaction {
+    #[prost(string, tag="1")]
+    pub txn_id: ::prost::alloc::string::String,
+    /// Index of the transaction in the block, as in 'Transaction'
+    #[prost(uint32, tag="2")]
+    pub index: u32,
+    #[prost(string, repeated, tag="3")]
+    pub signers: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Unset when the transaction failed, in which case none of its events took effect
+    #[prost(bool, tag="4")]
+    pub success: bool,
+    /// The events of the transaction, in execution order
+    #[prost(message, repeated, tag="5")]
+    pub events: ::prost::alloc::vec::Vec<Event>,
+    /// The token balances of the accounts holding the tracked token contract, before and after
+    /// the transaction
+    #[prost(message, repeated, tag="6")]
+    pub changes: ::prost::alloc::vec::Vec<BalanceChange>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct WrappedSolEvents {
+    #[prost(message, repeated, tag="1")]
+    pub data: ::prost::alloc::vec::Vec<WrappedSolEvent>,
+}
+/// A wrapping of SOL into, or an unwrapping of SOL out of, a token account of the native mint.
+/// SOL is wrapped by 'SyncNative' after lamports were deposited in the account, or by
+/// initializing an account that already holds lamports above its rent-exempt reserve, and
+/// unwrapped by 'CloseAccount'.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct WrappedSolEvent {
+    #[prost(string, tag="1")]
+    pub txn_id: ::prost::alloc::string::String,
+    /// The number of blocks produced before this one, which lags behind the slot as slots can be
+    /// skipped. Zero for blocks recorded before the block height was tracked.
+    #[prost(uint64, tag="2")]
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::u64_string"))]
+    pub block_height: u64,
+    #[prost(int64, tag="3")]
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::i64_string"))]
+    pub block_timestamp: i64,
+    #[prost(string, tag="4")]
+    pub block_hash: ::prost::alloc::string::String,
+    #[prost(uint32, tag="5")]
+    pub instruction_index: u32,
+    #[prost(enumeration="wrapped_sol_event::Kind", tag="6")]
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::wrapped_sol_kind"))]
+    pub kind: i32,
+    #[prost(string, tag="7")]
+    pub account: ::prost::alloc::string::String,
+    #[prost(string, tag="8")]
+    pub owner: ::prost::alloc::string::String,
+    /// The amount of wrapped SOL, in lamports
+    #[prost(uint64, tag="9")]
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::u64_string"))]
+    pub amount: u64,
+    /// The lamports deposited into (wrap) or returned from (unwrap) the account, which includes
+    /// the rent-exempt reserve when the account is initialized or closed
+    #[prost(uint64, tag="10")]
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::u64_string"))]
+    pub lamports: u64,
+    /// The account receiving the lamports of an unwrap, empty for a wrap
+    #[prost(string, tag="11")]
+    pub destination: ::prost::alloc::string::String,
+    /// The slot of the block, what 'sf.substreams.v1.Clock' reports as the block number
+    #[prost(uint64, tag="12")]
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::u64_string"))]
+    pub slot: u64,
+    #[prost(uint64, tag="13")]
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::u64_string"))]
+    pub parent_slot: u64,
+    #[prost(string, tag="14")]
+    pub previous_block_hash: ::prost::alloc::string::String,
+}
+/// Nested message and enum types in `WrappedSolEvent`.
+pub mod wrapped_sol_event {
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+    #[repr(i32)]
+    pub enum Kind {
+        Unspecified = 0,
+        Wrap = 1,
+        Unwrap = 2,
+    }
+    impl Kind {
+        /// String value of the enum field names used in the ProtoBuf definition.
+        ///
+        /// The values are not transformed in any way and thus are considered stable
+        /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+        pub fn as_str_name(&self) -> &'static str {
+            match self {
+                Kind::Unspecified => "UNSPECIFIED",
+                Kind::Wrap => "WRAP",
+                Kind::Unwrap => "UNWRAP",
+            }
+        }
+        /// Creates an enum from field names used in the ProtoBuf definition.
+        pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+            match value {
+                "UNSPECIFIED" => Some(Self::Unspecified),
+                "WRAP" => Some(Self::Wrap),
+                "UNWRAP" => Some(Self::Unwrap),
+                _ => None,
+            }
+        }
+    }
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AuthorityChanges {
+    #[prost(message, repeated, tag="1")]
+    pub data: ::prost::alloc::vec::Vec<AuthorityChange>,
+}
+/// A change of one of the authorities of a mint or token account, from 'SetAuthority', or
+/// the initial authorities of a mint from 'InitializeMint'.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AuthorityChange {
+    #[prost(string, tag="1")]
+    pub txn_id: ::prost::alloc::string::String,
+    /// The number of blocks produced before this one, which lags behind the slot as slots can be
+    /// skipped. Zero for blocks recorded before the block height was tracked.
+    #[prost(uint64, tag="2")]
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::u64_string"))]
+    pub block_height: u64,
+    #[prost(int64, tag="3")]
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::i64_string"))]
+    pub block_timestamp: i64,
+    #[prost(string, tag="4")]
+    pub block_hash: ::prost::alloc::string::String,
+    #[prost(uint32, tag="5")]
+    pub instruction_index: u32,
+    #[prost(string, tag="6")]
+    pub account: ::prost::alloc::string::String,
+    #[prost(enumeration="set_authority::AccountKind", tag="7")]
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::account_kind"))]
+    pub account_kind: i32,
+    #[prost(enumeration="set_authority::AuthorityType", tag="8")]
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::authority_type"))]
+    pub authority_type: i32,
+    /// Unset when the authority is set by the initialization of the mint
+    #[prost(string, optional, tag="9")]
+    pub old_authority: ::core::option::Option<::prost::alloc::string::String>,
+    /// Unset when the authority is removed
+    #[prost(string, optional, tag="10")]
+    pub new_authority: ::core::option::Option<::prost::alloc::string::String>,
+    /// Set when the mint tokens or freeze authority of a mint is removed, which is irreversible
+    #[prost(bool, tag="11")]
+    pub renounced: bool,
+    /// The slot of the block, what 'sf.substreams.v1.Clock' reports as the block number
+    #[prost(uint64, tag="12")]
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::u64_string"))]
+    pub slot: u64,
+    #[prost(uint64, tag="13")]
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::u64_string"))]
+    pub parent_slot: u64,
+    #[prost(string, tag="14")]
+    pub previous_block_hash: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FreezeAlerts {
+    #[prost(message, repeated, tag="1")]
+    pub data: ::prost::alloc::vec::Vec<FreezeAlert>,
+}
+/// Raised when the freeze authority of a mint freezes an account holding at least the
+/// 'min_amount' parameter of 'map_freeze_alerts'.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FreezeAlert {
+    #[prost(string, tag="1")]
+    pub txn_id: ::prost::alloc::string::String,
+    /// The number of blocks produced before this one, which lags behind the slot as slots can be
+    /// skipped. Zero for blocks recorded before the block height was tracked.
+    #[prost(uint64, tag="2")]
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::u64_string"))]
+    pub block_height: u64,
+    #[prost(int64, tag="3")]
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::i64_string"))]
+    pub block_timestamp: i64,
+    #[prost(string, tag="4")]
+    pub block_hash: ::prost::alloc::string::String,
+    #[prost(uint32, tag="5")]
+    pub instruction_index: u32,
+    #[prost(string, tag="6")]
+    pub account: ::prost::alloc::string::String,
+    #[prost(string, tag="7")]
+    pub owner: ::prost::alloc::string::String,
+    #[prost(string, tag="8")]
+    pub mint: ::prost::alloc::string::String,
+    #[prost(string, tag="9")]
+    pub freeze_authority: ::prost::alloc::string::String,
+    /// The token balance of the frozen account, a raw amount not adjusted for decimals
+    #[prost(uint64, tag="10")]
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::u64_string"))]
+    pub amount: u64,
+    #[prost(uint32, tag="11")]
+    pub decimals: u32,
+    /// The slot of the block, what 'sf.substreams.v1.Clock' reports as the block number
+    #[prost(uint64, tag="12")]
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::u64_string"))]
+    pub slot: u64,
+    #[prost(uint64, tag="13")]
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::u64_string"))]
+    pub parent_slot: u64,
+    #[prost(string, tag="14")]
+    pub previous_block_hash: ::prost::alloc::string::String,
 }
 // @@protoc_insertion_point(module)
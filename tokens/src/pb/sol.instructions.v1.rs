@@ -1,12 +1,14 @@
 // @generated
 // This file is @generated by prost-build.
 #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Instructions {
     #[prost(message, repeated, tag="1")]
     pub instructions: ::prost::alloc::vec::Vec<Instruction>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Instruction {
     #[prost(string, tag="1")]
@@ -14,6 +16,7 @@ pub struct Instruction {
     #[prost(string, repeated, tag="2")]
     pub accounts: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
     #[prost(bytes="vec", tag="3")]
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::base58"))]
     pub data: ::prost::alloc::vec::Vec<u8>,
     #[prost(string, tag="4")]
     pub tx_hash: ::prost::alloc::string::String,
@@ -0,0 +1,36 @@
+use crate::pb::sf::solana::spl::token::v1::{event::Type, Burn, Events, MintTo};
+use substreams::{
+    scalar::BigInt,
+    store::{StoreAdd, StoreAddBigInt, StoreNew},
+};
+
+/// Accumulates the supply of each mint, keyed by mint address, from the `MintTo` and `Burn`
+/// events.
+#[substreams::handlers::store]
+fn store_mint_supply(events: Events, store: StoreAddBigInt) {
+    for (ordinal, event) in events.data.iter().enumerate() {
+        let Some(event_type) = event.r#type.as_ref() else {
+            continue;
+        };
+
+        match event_type {
+            Type::MintTo(MintTo {
+                instruction,
+                accounts,
+            }) => store.add(
+                ordinal as u64,
+                &accounts.as_ref().unwrap().mint,
+                BigInt::from(instruction.as_ref().unwrap().amount),
+            ),
+            Type::Burn(Burn {
+                instruction,
+                accounts,
+            }) => store.add(
+                ordinal as u64,
+                &accounts.as_ref().unwrap().mint,
+                BigInt::from(instruction.as_ref().unwrap().amount).neg(),
+            ),
+            _ => {}
+        }
+    }
+}
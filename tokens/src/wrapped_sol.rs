@@ -0,0 +1,425 @@
+use std::collections::HashMap;
+
+use crate::instructions::{decode_instruction, TransactionSigners};
+use crate::map_block::{resolved_accounts, token_balance_amount};
+use crate::pb::{
+    sf::solana::spl::token::v1::{
+        event::Type, wrapped_sol_event::Kind, BlockMeta, CloseAccount, InitializeAccount,
+        SyncNative, Transfer, WrappedSolEvent, WrappedSolEvents,
+    },
+    sol::transactions::v1::Transactions,
+};
+use spl_token::solana_program::{program_pack::Pack, rent::Rent};
+use substreams::skip_empty_output;
+use substreams_solana::pb::sf::solana::r#type::v1::ConfirmedTransaction;
+
+/// Emits the wrapping and unwrapping of SOL in token accounts of the native mint, across all
+/// wallets and regardless of the `token_contract` parameter of `map_block`.
+///
+/// The amounts are derived from the token and lamport balances of the native accounts before
+/// and after the transaction, along with the token instructions touching them, so that an
+/// account created, wrapped, spent and closed within a single transaction is still accounted
+/// for. Failed transactions are skipped.
+#[substreams::handlers::map]
+fn map_wrapped_sol(
+    block: BlockMeta,
+    trxs: Transactions,
+) -> Result<WrappedSolEvents, substreams::errors::Error> {
+    skip_empty_output();
+
+    let mut data: Vec<WrappedSolEvent> = Vec::new();
+    for confirmed_txn in trxs.transactions {
+        match confirmed_txn.meta.as_ref() {
+            Some(meta) if meta.err.is_none() => {}
+            _ => continue,
+        }
+
+        let tx_id = confirmed_txn.id();
+        let balances = native_balances(&confirmed_txn);
+        let steps = native_steps(&confirmed_txn, &balances);
+
+        for mut event in to_wrapped_sol_events(&balances, steps) {
+            event.txn_id = tx_id.clone();
+            event.slot = block.slot;
+            event.block_height = block.block_height;
+            event.block_timestamp = block.block_timestamp;
+            event.block_hash = block.block_hash.clone();
+            event.parent_slot = block.parent_slot;
+            event.previous_block_hash = block.previous_block_hash.clone();
+            data.push(event);
+        }
+    }
+
+    Ok(WrappedSolEvents { data })
+}
+
+/// The balances of a token account of the native mint, from the token balances of the
+/// transaction. The lamports are the balances of the account before and after the transaction.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct NativeBalance {
+    owner: String,
+    /// Unset when the account was not a native account before the transaction
+    pre_amount: Option<u64>,
+    /// Unset when the account is not a native account after the transaction
+    post_amount: Option<u64>,
+    pre_lamports: u64,
+    post_lamports: u64,
+}
+
+/// A token instruction changing the wrapped amount of a native account.
+#[derive(Clone, Debug, PartialEq)]
+enum Step {
+    /// `InitializeAccount`, wrapping the lamports the account holds above its reserve
+    Initialize {
+        owner: String,
+    },
+    /// `SyncNative`, wrapping the lamports deposited since the last sync
+    Sync,
+    TransferIn(u64),
+    TransferOut(u64),
+    Close {
+        destination: String,
+    },
+}
+
+fn native_balances(trx: &ConfirmedTransaction) -> HashMap<String, NativeBalance> {
+    let meta = trx.meta.as_ref().unwrap();
+    let keys = resolved_accounts(trx);
+    let native_mint = spl_token::native_mint::ID.to_string();
+
+    let mut balances: HashMap<String, NativeBalance> = HashMap::new();
+    for (balance, pre) in meta
+        .pre_token_balances
+        .iter()
+        .map(|balance| (balance, true))
+        .chain(
+            meta.post_token_balances
+                .iter()
+                .map(|balance| (balance, false)),
+        )
+    {
+        if balance.mint != native_mint {
+            continue;
+        }
+        let index = balance.account_index as usize;
+        let Some(key) = keys.get(index) else {
+            continue;
+        };
+
+        let native = balances
+            .entry(key.clone())
+            .or_insert_with(|| NativeBalance {
+                owner: balance.owner.clone(),
+                pre_lamports: meta.pre_balances.get(index).copied().unwrap_or_default(),
+                post_lamports: meta.post_balances.get(index).copied().unwrap_or_default(),
+                ..Default::default()
+            });
+        if pre {
+            native.pre_amount = Some(token_balance_amount(balance));
+        } else {
+            native.post_amount = Some(token_balance_amount(balance));
+        }
+    }
+
+    balances
+}
+
+/// Returns the token instructions of the transaction touching a native account, along with
+/// their instruction index and the account. Accounts initialized with the native mint by the
+/// transaction are native even when they are closed before it ends.
+fn native_steps(
+    trx: &ConfirmedTransaction,
+    balances: &HashMap<String, NativeBalance>,
+) -> Vec<(u32, String, Step)> {
+    let native_mint = spl_token::native_mint::ID.to_string();
+    let mut initialized: Vec<String> = Vec::new();
+    let is_native = |key: &str, initialized: &[String]| {
+        balances.contains_key(key) || initialized.iter().any(|k| k == key)
+    };
+
+    let signers = TransactionSigners::new(trx);
+    let mut steps = Vec::new();
+    for (i, instruction) in trx.walk_instructions().enumerate() {
+        let i = i as u32;
+        let Ok(Some(event_type)) = decode_instruction(&instruction, &signers) else {
+            continue;
+        };
+
+        match event_type {
+            Type::InitializeAccount(InitializeAccount { accounts, .. }) => {
+                let accounts = accounts.unwrap();
+                if accounts.mint != native_mint {
+                    continue;
+                }
+                initialized.push(accounts.account.clone());
+                steps.push((
+                    i,
+                    accounts.account,
+                    Step::Initialize {
+                        owner: accounts.owner,
+                    },
+                ));
+            }
+            Type::SyncNative(SyncNative { accounts, .. }) => {
+                let account = accounts.unwrap().native_token_account;
+                if is_native(&account, &initialized) {
+                    steps.push((i, account, Step::Sync));
+                }
+            }
+            Type::Transfer(Transfer {
+                instruction,
+                accounts,
+                ..
+            }) => {
+                let accounts = accounts.unwrap();
+                if !is_native(&accounts.source, &initialized) {
+                    continue;
+                }
+                let amount = instruction.unwrap().amount;
+                steps.push((i, accounts.source, Step::TransferOut(amount)));
+                steps.push((i, accounts.destination, Step::TransferIn(amount)));
+            }
+            Type::CloseAccount(CloseAccount { accounts, .. }) => {
+                let accounts = accounts.unwrap();
+                if is_native(&accounts.account, &initialized) {
+                    steps.push((
+                        i,
+                        accounts.account,
+                        Step::Close {
+                            destination: accounts.destination,
+                        },
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    steps
+}
+
+/// Replays the steps of each native account from its balance before the transaction.
+///
+/// The lamports deposited into an account aren't decoded, the amount wrapped by an
+/// `InitializeAccount` or a `SyncNative` is instead the least amount that the transfers out of
+/// the account until the next wrap or its close require, and for an account still open at the
+/// end of the transaction the rest of its balance after the transaction. The reserve of an
+/// account is the lamports it holds above its wrapped amount before or after the transaction,
+/// or the rent-exempt reserve when it only lived within the transaction.
+fn to_wrapped_sol_events(
+    balances: &HashMap<String, NativeBalance>,
+    steps: Vec<(u32, String, Step)>,
+) -> Vec<WrappedSolEvent> {
+    let mut accounts: Vec<String> = Vec::new();
+    for (_, account, _) in &steps {
+        if !accounts.contains(account) {
+            accounts.push(account.clone());
+        }
+    }
+
+    let mut events: Vec<WrappedSolEvent> = Vec::new();
+    for account in accounts {
+        let balance = balances.get(&account).cloned().unwrap_or_default();
+        let reserve = match (balance.pre_amount, balance.post_amount) {
+            (Some(amount), _) => balance.pre_lamports.saturating_sub(amount),
+            (None, Some(amount)) => balance.post_lamports.saturating_sub(amount),
+            (None, None) => rent_exempt_reserve(),
+        };
+        let mut owner = balance.owner.clone();
+        let mut amount = balance.pre_amount.unwrap_or_default();
+        // The last wrap, its instruction index, amount and whether it initialized the account
+        let mut wrap: Option<(u32, u64, bool)> = None;
+
+        for (i, _, step) in steps.iter().filter(|(_, key, _)| *key == account) {
+            match step {
+                Step::Initialize { owner: new_owner } => {
+                    events.extend(wrap_event(wrap.take(), &account, &owner, reserve));
+                    owner = new_owner.clone();
+                    amount = 0;
+                    wrap = Some((*i, 0, true));
+                }
+                Step::Sync => {
+                    events.extend(wrap_event(wrap.take(), &account, &owner, reserve));
+                    wrap = Some((*i, 0, false));
+                }
+                Step::TransferIn(transferred) => amount = amount.saturating_add(*transferred),
+                Step::TransferOut(transferred) => {
+                    if let Some((_, wrapped, _)) = wrap.as_mut() {
+                        let missing = transferred.saturating_sub(amount);
+                        *wrapped += missing;
+                        amount += missing;
+                    }
+                    amount = amount.saturating_sub(*transferred);
+                }
+                Step::Close { destination } => {
+                    events.extend(wrap_event(wrap.take(), &account, &owner, reserve));
+                    let mut event = wrapped_sol_event(
+                        Kind::Unwrap,
+                        &account,
+                        &owner,
+                        amount,
+                        amount.saturating_add(reserve),
+                    );
+                    event.instruction_index = *i;
+                    event.destination = destination.clone();
+                    events.push(event);
+                    amount = 0;
+                }
+            }
+        }
+
+        // A wrap left pending is on an account open at the end of the transaction
+        if let Some((_, wrapped, _)) = wrap.as_mut() {
+            *wrapped += balance
+                .post_amount
+                .unwrap_or_default()
+                .saturating_sub(amount);
+        }
+        events.extend(wrap_event(wrap, &account, &owner, reserve));
+    }
+
+    events.sort_by_key(|event| event.instruction_index);
+    events
+}
+
+fn wrap_event(
+    wrap: Option<(u32, u64, bool)>,
+    account: &str,
+    owner: &str,
+    reserve: u64,
+) -> Option<WrappedSolEvent> {
+    let (instruction_index, amount, initialized) = wrap?;
+    let lamports = if initialized {
+        amount.saturating_add(reserve)
+    } else {
+        amount
+    };
+
+    (amount > 0).then(|| WrappedSolEvent {
+        instruction_index,
+        ..wrapped_sol_event(Kind::Wrap, account, owner, amount, lamports)
+    })
+}
+
+/// The rent-exempt reserve of a token account, which is not part of its wrapped amount.
+pub(crate) fn rent_exempt_reserve() -> u64 {
+    Rent::default().minimum_balance(spl_token::state::Account::LEN)
+}
+
+fn wrapped_sol_event(
+    kind: Kind,
+    account: &str,
+    owner: &str,
+    amount: u64,
+    lamports: u64,
+) -> WrappedSolEvent {
+    WrappedSolEvent {
+        kind: kind as i32,
+        account: account.to_string(),
+        owner: owner.to_string(),
+        amount,
+        lamports,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(i: u32, step: Step) -> (u32, String, Step) {
+        (i, "account".to_string(), step)
+    }
+
+    fn event(
+        kind: Kind,
+        instruction_index: u32,
+        amount: u64,
+        lamports: u64,
+        destination: &str,
+    ) -> WrappedSolEvent {
+        WrappedSolEvent {
+            instruction_index,
+            destination: destination.to_string(),
+            ..wrapped_sol_event(kind, "account", "owner", amount, lamports)
+        }
+    }
+
+    #[test]
+    fn test_account_wrapped_spent_and_closed_within_transaction() {
+        let reserve = rent_exempt_reserve();
+        let steps = vec![
+            step(
+                0,
+                Step::Initialize {
+                    owner: "owner".to_string(),
+                },
+            ),
+            step(2, Step::Sync),
+            step(3, Step::TransferOut(1_000)),
+            step(4, Step::TransferIn(30)),
+            step(
+                5,
+                Step::Close {
+                    destination: "owner".to_string(),
+                },
+            ),
+        ];
+
+        assert_eq!(
+            to_wrapped_sol_events(&HashMap::new(), steps),
+            vec![
+                event(Kind::Wrap, 2, 1_000, 1_000, ""),
+                event(Kind::Unwrap, 5, 30, 30 + reserve, "owner"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_existing_account_synced() {
+        let balances = HashMap::from([(
+            "account".to_string(),
+            NativeBalance {
+                owner: "owner".to_string(),
+                pre_amount: Some(100),
+                post_amount: Some(130),
+                pre_lamports: 2_100,
+                post_lamports: 2_130,
+            },
+        )]);
+        let steps = vec![step(1, Step::Sync), step(2, Step::TransferOut(20))];
+
+        assert_eq!(
+            to_wrapped_sol_events(&balances, steps),
+            vec![event(Kind::Wrap, 1, 50, 50, "")]
+        );
+    }
+
+    #[test]
+    fn test_existing_account_closed() {
+        let balances = HashMap::from([(
+            "account".to_string(),
+            NativeBalance {
+                owner: "owner".to_string(),
+                pre_amount: Some(100),
+                post_amount: None,
+                pre_lamports: 2_100,
+                post_lamports: 0,
+            },
+        )]);
+        let steps = vec![
+            step(0, Step::TransferIn(5)),
+            step(
+                1,
+                Step::Close {
+                    destination: "wallet".to_string(),
+                },
+            ),
+        ];
+
+        assert_eq!(
+            to_wrapped_sol_events(&balances, steps),
+            vec![event(Kind::Unwrap, 1, 105, 2_105, "wallet")]
+        );
+    }
+}
@@ -0,0 +1,711 @@
+//! Builds random instructions with the `spl_token::instruction` builders and checks that
+//! decoding them recovers every amount, decimals, authority and account.
+
+use proptest::{collection::vec, prelude::*};
+use spl_token::{
+    instruction::{self, AuthorityType as SplAuthorityType, MAX_SIGNERS},
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+    },
+};
+use substreams_solana::pb::sf::solana::r#type::v1::{
+    CompiledInstruction, ConfirmedTransaction, Message, MessageHeader, Transaction,
+    TransactionStatusMeta,
+};
+use substreams_solana_spl_token::{
+    decode_instruction,
+    pb::sf::solana::spl::token::v1::{
+        approve::{ApproveAccounts, ApproveInstruction},
+        burn::{BurnAccounts, BurnInstruction},
+        close_account::{CloseAccountAccounts, CloseAccountInstruction},
+        event::Type,
+        freeze_account::{FreezeAccountAccounts, FreezeAccountInstruction},
+        initialize_account::{
+            InitializeAccountAccounts, InitializeAccountInstruction, InitializeAccountVersion,
+        },
+        initialize_immutable_owner::{
+            InitializeImmutableOwnerAccounts, InitializeImmutableOwnerInstruction,
+        },
+        initialize_mint::{
+            InitializeMintAccounts, InitializeMintInstruction, InitializeMintVersion,
+        },
+        initialize_multisig::{
+            InitializeMultisigAccounts, InitializeMultisigInstruction, InitializeMultisigVersion,
+        },
+        mint_to::{MintToAccounts, MintToInstruction},
+        revoke::{RevokeAccounts, RevokeInstruction},
+        set_authority::{
+            AccountKind, AuthorityType, SetAuthorityAccounts, SetAuthorityInstruction,
+        },
+        signer::Kind,
+        sync_native::{SyncNativeAccounts, SyncNativeInstruction},
+        thaw_account::{ThawAccountAccounts, ThawAccountInstruction},
+        transfer::{TransferAccounts, TransferInstruction},
+        Approve, Burn, CloseAccount, FreezeAccount, InitializeAccount, InitializeImmutableOwner,
+        InitializeMint, InitializeMultisig, MintTo, MultiSignature, Revoke, SetAuthority, Signer,
+        SingleSignature, SyncNative, ThawAccount, Transfer,
+    },
+    TransactionSigners,
+};
+
+/// The authority of an instruction, a multisig account when it comes with signers.
+#[derive(Clone, Debug)]
+struct Authority {
+    account: Pubkey,
+    signers: Vec<Pubkey>,
+}
+
+impl Authority {
+    fn signer_pubkeys(&self) -> Vec<&Pubkey> {
+        self.signers.iter().collect()
+    }
+
+    fn to_signer(&self) -> Option<Signer> {
+        Some(Signer {
+            kind: Some(if self.signers.is_empty() {
+                Kind::Single(SingleSignature {
+                    signer: self.account.to_string(),
+                })
+            } else {
+                Kind::Multisig(MultiSignature {
+                    multisig_account: self.account.to_string(),
+                    signers: self.signers.iter().map(Pubkey::to_string).collect(),
+                    ..Default::default()
+                })
+            }),
+        })
+    }
+}
+
+fn pubkey() -> impl Strategy<Value = Pubkey> {
+    any::<[u8; 32]>().prop_map(Pubkey::new_from_array)
+}
+
+/// A single signer or a multisig account with up to `MAX_SIGNERS` signers. A multisig account
+/// without any signer can't meet its threshold and is built exactly like a single signer, so
+/// it isn't generated.
+fn authority() -> impl Strategy<Value = Authority> {
+    (pubkey(), vec(pubkey(), 0..=MAX_SIGNERS))
+        .prop_map(|(account, signers)| Authority { account, signers })
+}
+
+fn authority_types() -> impl Strategy<Value = (SplAuthorityType, AuthorityType, AccountKind)> {
+    prop_oneof![
+        Just((
+            SplAuthorityType::MintTokens,
+            AuthorityType::AuthorityMintTokens,
+            AccountKind::Mint
+        )),
+        Just((
+            SplAuthorityType::FreezeAccount,
+            AuthorityType::AuthorityFreezeAccount,
+            AccountKind::Mint
+        )),
+        Just((
+            SplAuthorityType::AccountOwner,
+            AuthorityType::AuthorityAccountOwner,
+            AccountKind::TokenAccount
+        )),
+        Just((
+            SplAuthorityType::CloseAccount,
+            AuthorityType::AuthorityCloseAccount,
+            AccountKind::TokenAccount
+        )),
+    ]
+}
+
+/// Decodes the instruction as the only instruction of a transaction, the accounts flagged as
+/// signers by the builder being the signers of the transaction and coming first in its account
+/// keys, as in a compiled message.
+fn decode(instruction: Instruction) -> Type {
+    let signers: Vec<Pubkey> = instruction
+        .accounts
+        .iter()
+        .filter(|meta| meta.is_signer)
+        .map(|meta| meta.pubkey)
+        .collect();
+    let keys: Vec<Pubkey> = signers
+        .iter()
+        .copied()
+        .chain(std::iter::once(instruction.program_id))
+        .chain(
+            instruction
+                .accounts
+                .iter()
+                .filter(|meta| !meta.is_signer)
+                .map(|meta| meta.pubkey),
+        )
+        .collect();
+    let index = |key: &Pubkey| keys.iter().position(|k| k == key).unwrap() as u8;
+
+    let trx = ConfirmedTransaction {
+        transaction: Some(Transaction {
+            signatures: vec![vec![0; 64]; signers.len()],
+            message: Some(Message {
+                header: Some(MessageHeader {
+                    num_required_signatures: signers.len() as u32,
+                    ..Default::default()
+                }),
+                account_keys: keys.iter().map(|key| key.to_bytes().to_vec()).collect(),
+                instructions: vec![CompiledInstruction {
+                    program_id_index: index(&instruction.program_id) as u32,
+                    accounts: instruction
+                        .accounts
+                        .iter()
+                        .map(|meta| index(&meta.pubkey))
+                        .collect(),
+                    data: instruction.data,
+                }],
+                ..Default::default()
+            }),
+        }),
+        meta: Some(TransactionStatusMeta::default()),
+    };
+
+    let instruction = trx.walk_instructions().next().unwrap();
+    decode_instruction(&instruction, &TransactionSigners::new(&trx))
+        .unwrap()
+        .expect("instruction of the token program")
+}
+
+proptest! {
+    // Programs invoking the token program may append accounts of their own after the
+    // authority and its signers, those don't sign and aren't signers of a multisig
+    #[test]
+    fn transfer_with_extra_accounts(
+        source in pubkey(),
+        destination in pubkey(),
+        authority in authority(),
+        amount in any::<u64>(),
+        extra in vec(pubkey(), 1..=3),
+    ) {
+        let mut built = instruction::transfer(
+            &spl_token::ID,
+            &source,
+            &destination,
+            &authority.account,
+            &authority.signer_pubkeys(),
+            amount,
+        )
+        .unwrap();
+        built
+            .accounts
+            .extend(extra.iter().map(|key| AccountMeta::new_readonly(*key, false)));
+
+        prop_assert_eq!(
+            decode(built),
+            Type::Transfer(Transfer {
+                instruction: Some(TransferInstruction {
+                    amount,
+                    decimals: None,
+                }),
+                accounts: Some(TransferAccounts {
+                    source: source.to_string(),
+                    destination: destination.to_string(),
+                    signer: authority.to_signer(),
+                    token_mint: None,
+                }),
+                memo: None,
+            })
+        );
+    }
+
+    #[test]
+    fn transfer(
+        source in pubkey(),
+        destination in pubkey(),
+        authority in authority(),
+        amount in any::<u64>(),
+    ) {
+        let built = instruction::transfer(
+            &spl_token::ID,
+            &source,
+            &destination,
+            &authority.account,
+            &authority.signer_pubkeys(),
+            amount,
+        )
+        .unwrap();
+
+        prop_assert_eq!(
+            decode(built),
+            Type::Transfer(Transfer {
+                instruction: Some(TransferInstruction {
+                    amount,
+                    decimals: None,
+                }),
+                accounts: Some(TransferAccounts {
+                    source: source.to_string(),
+                    destination: destination.to_string(),
+                    signer: authority.to_signer(),
+                    token_mint: None,
+                }),
+                memo: None,
+            })
+        );
+    }
+
+    #[test]
+    fn transfer_checked(
+        source in pubkey(),
+        mint in pubkey(),
+        destination in pubkey(),
+        authority in authority(),
+        amount in any::<u64>(),
+        decimals in any::<u8>(),
+    ) {
+        let built = instruction::transfer_checked(
+            &spl_token::ID,
+            &source,
+            &mint,
+            &destination,
+            &authority.account,
+            &authority.signer_pubkeys(),
+            amount,
+            decimals,
+        )
+        .unwrap();
+
+        prop_assert_eq!(
+            decode(built),
+            Type::Transfer(Transfer {
+                instruction: Some(TransferInstruction {
+                    amount,
+                    decimals: Some(decimals as u32),
+                }),
+                accounts: Some(TransferAccounts {
+                    source: source.to_string(),
+                    destination: destination.to_string(),
+                    signer: authority.to_signer(),
+                    token_mint: Some(mint.to_string()),
+                }),
+                memo: None,
+            })
+        );
+    }
+
+    #[test]
+    fn approve(
+        source in pubkey(),
+        delegate in pubkey(),
+        authority in authority(),
+        amount in any::<u64>(),
+    ) {
+        let built = instruction::approve(
+            &spl_token::ID,
+            &source,
+            &delegate,
+            &authority.account,
+            &authority.signer_pubkeys(),
+            amount,
+        )
+        .unwrap();
+
+        prop_assert_eq!(
+            decode(built),
+            Type::Approve(Approve {
+                instruction: Some(ApproveInstruction {
+                    amount,
+                    decimals: None,
+                }),
+                accounts: Some(ApproveAccounts {
+                    source: source.to_string(),
+                    delegate: delegate.to_string(),
+                    signer: authority.to_signer(),
+                    token_mint: None,
+                }),
+            })
+        );
+    }
+
+    #[test]
+    fn approve_checked(
+        source in pubkey(),
+        mint in pubkey(),
+        delegate in pubkey(),
+        authority in authority(),
+        amount in any::<u64>(),
+        decimals in any::<u8>(),
+    ) {
+        let built = instruction::approve_checked(
+            &spl_token::ID,
+            &source,
+            &mint,
+            &delegate,
+            &authority.account,
+            &authority.signer_pubkeys(),
+            amount,
+            decimals,
+        )
+        .unwrap();
+
+        prop_assert_eq!(
+            decode(built),
+            Type::Approve(Approve {
+                instruction: Some(ApproveInstruction {
+                    amount,
+                    decimals: Some(decimals as u32),
+                }),
+                accounts: Some(ApproveAccounts {
+                    source: source.to_string(),
+                    delegate: delegate.to_string(),
+                    signer: authority.to_signer(),
+                    token_mint: Some(mint.to_string()),
+                }),
+            })
+        );
+    }
+
+    #[test]
+    fn revoke(source in pubkey(), authority in authority()) {
+        let built = instruction::revoke(
+            &spl_token::ID,
+            &source,
+            &authority.account,
+            &authority.signer_pubkeys(),
+        )
+        .unwrap();
+
+        prop_assert_eq!(
+            decode(built),
+            Type::Revoke(Revoke {
+                instruction: Some(RevokeInstruction {}),
+                accounts: Some(RevokeAccounts {
+                    source: source.to_string(),
+                    signer: authority.to_signer(),
+                }),
+            })
+        );
+    }
+
+    #[test]
+    fn set_authority(
+        account in pubkey(),
+        (spl_authority_type, authority_type, account_kind) in authority_types(),
+        new_authority in proptest::option::of(pubkey()),
+        authority in authority(),
+    ) {
+        let built = instruction::set_authority(
+            &spl_token::ID,
+            &account,
+            new_authority.as_ref(),
+            spl_authority_type,
+            &authority.account,
+            &authority.signer_pubkeys(),
+        )
+        .unwrap();
+
+        prop_assert_eq!(
+            decode(built),
+            Type::SetAuthority(SetAuthority {
+                instruction: Some(SetAuthorityInstruction {
+                    authority_type: authority_type as i32,
+                    new_authority: new_authority.map(|a| a.to_string()),
+                }),
+                accounts: Some(SetAuthorityAccounts {
+                    account: account.to_string(),
+                    current_authority: authority.account.to_string(),
+                    account_kind: account_kind as i32,
+                    signer: authority.to_signer(),
+                }),
+            })
+        );
+    }
+
+    #[test]
+    fn mint_to(
+        mint in pubkey(),
+        destination in pubkey(),
+        authority in authority(),
+        amount in any::<u64>(),
+        decimals in proptest::option::of(any::<u8>()),
+    ) {
+        let built = match decimals {
+            Some(decimals) => instruction::mint_to_checked(
+                &spl_token::ID,
+                &mint,
+                &destination,
+                &authority.account,
+                &authority.signer_pubkeys(),
+                amount,
+                decimals,
+            ),
+            None => instruction::mint_to(
+                &spl_token::ID,
+                &mint,
+                &destination,
+                &authority.account,
+                &authority.signer_pubkeys(),
+                amount,
+            ),
+        }
+        .unwrap();
+
+        prop_assert_eq!(
+            decode(built),
+            Type::MintTo(MintTo {
+                instruction: Some(MintToInstruction {
+                    amount,
+                    decimals: decimals.map(u32::from),
+                }),
+                accounts: Some(MintToAccounts {
+                    mint: mint.to_string(),
+                    destination: destination.to_string(),
+                    mint_authority: authority.to_signer(),
+                }),
+            })
+        );
+    }
+
+    #[test]
+    fn burn(
+        source in pubkey(),
+        mint in pubkey(),
+        authority in authority(),
+        amount in any::<u64>(),
+        decimals in proptest::option::of(any::<u8>()),
+    ) {
+        let built = match decimals {
+            Some(decimals) => instruction::burn_checked(
+                &spl_token::ID,
+                &source,
+                &mint,
+                &authority.account,
+                &authority.signer_pubkeys(),
+                amount,
+                decimals,
+            ),
+            None => instruction::burn(
+                &spl_token::ID,
+                &source,
+                &mint,
+                &authority.account,
+                &authority.signer_pubkeys(),
+                amount,
+            ),
+        }
+        .unwrap();
+
+        prop_assert_eq!(
+            decode(built),
+            Type::Burn(Burn {
+                instruction: Some(BurnInstruction {
+                    amount,
+                    decimals: decimals.map(u32::from),
+                }),
+                accounts: Some(BurnAccounts {
+                    source: source.to_string(),
+                    mint: mint.to_string(),
+                    signer: authority.to_signer(),
+                }),
+            })
+        );
+    }
+
+    #[test]
+    fn close_account(account in pubkey(), destination in pubkey(), authority in authority()) {
+        let built = instruction::close_account(
+            &spl_token::ID,
+            &account,
+            &destination,
+            &authority.account,
+            &authority.signer_pubkeys(),
+        )
+        .unwrap();
+
+        prop_assert_eq!(
+            decode(built),
+            Type::CloseAccount(CloseAccount {
+                instruction: Some(CloseAccountInstruction {}),
+                accounts: Some(CloseAccountAccounts {
+                    account: account.to_string(),
+                    destination: destination.to_string(),
+                    signer: authority.to_signer(),
+                }),
+                mint: String::new(),
+                amount: 0,
+                reclaimed_lamports: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn freeze_and_thaw_account(account in pubkey(), mint in pubkey(), authority in authority()) {
+        let freeze = instruction::freeze_account(
+            &spl_token::ID,
+            &account,
+            &mint,
+            &authority.account,
+            &authority.signer_pubkeys(),
+        )
+        .unwrap();
+        let thaw = instruction::thaw_account(
+            &spl_token::ID,
+            &account,
+            &mint,
+            &authority.account,
+            &authority.signer_pubkeys(),
+        )
+        .unwrap();
+
+        prop_assert_eq!(
+            decode(freeze),
+            Type::FreezeAccount(FreezeAccount {
+                instruction: Some(FreezeAccountInstruction {}),
+                accounts: Some(FreezeAccountAccounts {
+                    account: account.to_string(),
+                    mint: mint.to_string(),
+                    mint_freeze_authority: authority.to_signer(),
+                }),
+            })
+        );
+        prop_assert_eq!(
+            decode(thaw),
+            Type::ThawAccount(ThawAccount {
+                instruction: Some(ThawAccountInstruction {}),
+                accounts: Some(ThawAccountAccounts {
+                    account: account.to_string(),
+                    mint: mint.to_string(),
+                    mint_freeze_authority: authority.to_signer(),
+                }),
+            })
+        );
+    }
+
+    #[test]
+    fn initialize_mint(
+        mint in pubkey(),
+        mint_authority in pubkey(),
+        freeze_authority in proptest::option::of(pubkey()),
+        decimals in any::<u8>(),
+        version in prop_oneof![Just(InitializeMintVersion::V1), Just(InitializeMintVersion::V2)],
+    ) {
+        let build = match version {
+            InitializeMintVersion::V2 => instruction::initialize_mint2,
+            _ => instruction::initialize_mint,
+        };
+        let built = build(
+            &spl_token::ID,
+            &mint,
+            &mint_authority,
+            freeze_authority.as_ref(),
+            decimals,
+        )
+        .unwrap();
+
+        prop_assert_eq!(
+            decode(built),
+            Type::InitializeMint(InitializeMint {
+                version: version as i32,
+                instruction: Some(InitializeMintInstruction {
+                    mint_authority: mint_authority.to_string(),
+                    freeze_authority: freeze_authority.map(|a| a.to_string()),
+                    decimals: decimals as u32,
+                }),
+                accounts: Some(InitializeMintAccounts {
+                    mint: mint.to_string(),
+                }),
+            })
+        );
+    }
+
+    #[test]
+    fn initialize_account(
+        account in pubkey(),
+        mint in pubkey(),
+        owner in pubkey(),
+        version in prop_oneof![
+            Just(InitializeAccountVersion::V1),
+            Just(InitializeAccountVersion::V2),
+            Just(InitializeAccountVersion::V3),
+        ],
+    ) {
+        let build = match version {
+            InitializeAccountVersion::V2 => instruction::initialize_account2,
+            InitializeAccountVersion::V3 => instruction::initialize_account3,
+            _ => instruction::initialize_account,
+        };
+        let built = build(&spl_token::ID, &account, &mint, &owner).unwrap();
+
+        prop_assert_eq!(
+            decode(built),
+            Type::InitializeAccount(InitializeAccount {
+                version: version as i32,
+                instruction: Some(InitializeAccountInstruction {}),
+                accounts: Some(InitializeAccountAccounts {
+                    account: account.to_string(),
+                    mint: mint.to_string(),
+                    owner: owner.to_string(),
+                }),
+                is_associated_account: false,
+            })
+        );
+    }
+
+    #[test]
+    fn initialize_multisig(
+        (multisig, signers, m) in (pubkey(), vec(pubkey(), 1..=MAX_SIGNERS)).prop_flat_map(
+            |(multisig, signers)| {
+                let count = signers.len() as u8;
+                (Just(multisig), Just(signers), 1..=count)
+            },
+        ),
+        version in prop_oneof![
+            Just(InitializeMultisigVersion::V1),
+            Just(InitializeMultisigVersion::V2),
+        ],
+    ) {
+        let build = match version {
+            InitializeMultisigVersion::V2 => instruction::initialize_multisig2,
+            _ => instruction::initialize_multisig,
+        };
+        let built = build(
+            &spl_token::ID,
+            &multisig,
+            &signers.iter().collect::<Vec<_>>(),
+            m,
+        )
+        .unwrap();
+
+        prop_assert_eq!(
+            decode(built),
+            Type::InitializeMultisig(InitializeMultisig {
+                version: version as i32,
+                instruction: Some(InitializeMultisigInstruction {
+                    signature_count_threshold: m as u32,
+                }),
+                accounts: Some(InitializeMultisigAccounts {
+                    account: multisig.to_string(),
+                    signers: signers.iter().map(Pubkey::to_string).collect(),
+                }),
+            })
+        );
+    }
+
+    #[test]
+    fn sync_native_and_initialize_immutable_owner(account in pubkey()) {
+        let sync_native = instruction::sync_native(&spl_token::ID, &account).unwrap();
+        let initialize_immutable_owner =
+            instruction::initialize_immutable_owner(&spl_token::ID, &account).unwrap();
+
+        prop_assert_eq!(
+            decode(sync_native),
+            Type::SyncNative(SyncNative {
+                instruction: Some(SyncNativeInstruction {}),
+                accounts: Some(SyncNativeAccounts {
+                    native_token_account: account.to_string(),
+                }),
+            })
+        );
+        prop_assert_eq!(
+            decode(initialize_immutable_owner),
+            Type::InitializeImmutableOwner(InitializeImmutableOwner {
+                instruction: Some(InitializeImmutableOwnerInstruction {}),
+                accounts: Some(InitializeImmutableOwnerAccounts {
+                    account: account.to_string(),
+                }),
+            })
+        );
+    }
+}